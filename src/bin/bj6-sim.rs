@@ -0,0 +1,113 @@
+//! Runs turns of a board description without rendering anything.
+//!
+//! Usage: `bj6-sim <board.toml> [--turns N] [--actor-types PATH] [--config PATH] [--rounds PATH]`
+
+use std::{path::PathBuf, process::ExitCode};
+
+use bj6::{
+    data::game_config::GameConfig,
+    model::{actor_types::ActorTypes, rounds::RoundSchedule},
+    sim::{self, Scenario, SimError},
+};
+
+const USAGE: &str =
+    "usage: bj6-sim <board.toml> [--turns N] [--actor-types PATH] [--config PATH] [--rounds PATH]";
+
+struct Args {
+    board: PathBuf,
+    turns: Option<u32>,
+    actor_types: PathBuf,
+    config: PathBuf,
+    rounds: PathBuf,
+}
+
+fn parse_args() -> Result<Args, String> {
+    let mut board = None;
+    let mut turns = None;
+    let mut actor_types = PathBuf::from("assets/all.actor_types.toml");
+    let mut config = PathBuf::from("assets/game.config.toml");
+    let mut rounds = PathBuf::from("assets/all.rounds.toml");
+
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--turns" => {
+                let value = args.next().ok_or("--turns needs a value")?;
+                turns = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid turn count `{value}`"))?,
+                );
+            }
+            "--actor-types" => {
+                actor_types = args.next().ok_or("--actor-types needs a path")?.into();
+            }
+            "--config" => {
+                config = args.next().ok_or("--config needs a path")?.into();
+            }
+            "--rounds" => {
+                rounds = args.next().ok_or("--rounds needs a path")?.into();
+            }
+            "-h" | "--help" => return Err(USAGE.to_string()),
+            _ if board.is_none() && !arg.starts_with('-') => board = Some(PathBuf::from(arg)),
+            _ => return Err(format!("unexpected argument `{arg}`\n{USAGE}")),
+        }
+    }
+
+    Ok(Args {
+        board: board.ok_or(USAGE)?,
+        turns,
+        actor_types,
+        config,
+        rounds,
+    })
+}
+
+fn run(args: Args) -> Result<(), SimError> {
+    let config: GameConfig = sim::load_toml(&args.config)?;
+    let actor_types: ActorTypes = sim::load_toml(&args.actor_types)?;
+    let schedule: RoundSchedule = sim::load_toml(&args.rounds)?;
+    let scenario: Scenario = sim::load_toml(&args.board)?;
+
+    let game = scenario.build_game(&config, &actor_types, &schedule)?;
+    println!("seed: {}", game.seed());
+    let turns = args.turns.unwrap_or(scenario.turns);
+    for report in sim::run_turns(game, turns, &config, &schedule) {
+        println!("== turn {} (round {}) ==", report.turn, report.round);
+        for cmd in &report.cmds {
+            println!("{cmd:?}");
+        }
+        println!(
+            "gold: {} (+{} this turn), total gold: {}, turns left: {}",
+            report.gold, report.gold_this_turn, report.total_gold, report.turns_left
+        );
+        println!(
+            "income: +{} interest, +{} stipend, +{} streak",
+            report.income.interest, report.income.stipend, report.income.streak
+        );
+        if report.game_over {
+            println!("game over");
+        }
+        if report.victory {
+            println!("victory");
+        }
+    }
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = match parse_args() {
+        Ok(args) => args,
+        Err(message) => {
+            eprintln!("{message}");
+            return ExitCode::FAILURE;
+        }
+    };
+    match run(args) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            eprintln!("{err}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,85 @@
+//! Problems found in the data files, shown on top of everything until fixed.
+
+use bevy::{platform::collections::HashMap, prelude::*};
+
+use crate::theme::palette::{ERROR_BACKGROUND, ERROR_TEXT};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<DataErrors>();
+    app.add_systems(
+        Update,
+        update_error_overlay.run_if(resource_changed::<DataErrors>),
+    );
+}
+
+/// Errors for each data file, by asset path. A file is removed once it loads cleanly.
+#[derive(Resource, Debug, Default)]
+pub struct DataErrors(HashMap<String, Vec<String>>);
+
+impl DataErrors {
+    pub fn set(&mut self, path: impl Into<String>, errors: Vec<String>) {
+        let path = path.into();
+        for error in &errors {
+            warn!("{path}: {error}");
+        }
+        if errors.is_empty() {
+            self.0.remove(&path);
+        } else {
+            self.0.insert(path, errors);
+        }
+    }
+
+    fn report(&self) -> Option<String> {
+        let mut paths: Vec<_> = self.0.keys().collect();
+        paths.sort();
+        let lines: Vec<String> = paths
+            .into_iter()
+            .flat_map(|path| {
+                std::iter::once(format!("{path}:"))
+                    .chain(self.0[path].iter().map(|error| format!("  {error}")))
+            })
+            .collect();
+        (!lines.is_empty()).then(|| {
+            format!(
+                "Data errors, the last good data is still in use:\n{}",
+                lines.join("\n")
+            )
+        })
+    }
+}
+
+#[derive(Component)]
+struct DataErrorOverlay;
+
+fn update_error_overlay(
+    mut commands: Commands,
+    data_errors: Res<DataErrors>,
+    q_overlay: Query<Entity, With<DataErrorOverlay>>,
+) {
+    for entity in q_overlay.iter() {
+        commands.entity(entity).despawn();
+    }
+    let Some(report) = data_errors.report() else {
+        return;
+    };
+    commands.spawn((
+        Name::new("Data Error Overlay"),
+        DataErrorOverlay,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.),
+            bottom: Val::Px(10.),
+            padding: UiRect::all(Val::Px(10.)),
+            ..default()
+        },
+        BackgroundColor(ERROR_BACKGROUND),
+        GlobalZIndex(100),
+        Pickable::IGNORE,
+        children![(
+            Text(report),
+            TextFont::from_font_size(18.),
+            TextColor(ERROR_TEXT),
+            Pickable::IGNORE,
+        )],
+    ));
+}
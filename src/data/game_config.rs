@@ -1,5 +1,11 @@
 use bevy::prelude::*;
 
+use crate::model::{
+    income::IncomeRules,
+    runner::{ActivationOrder, TurnLimits},
+    shop::{RarityWeights, ShopRules},
+};
+
 #[derive(Resource)]
 #[allow(dead_code)]
 pub struct GameConfigHandle(pub Handle<GameConfig>);
@@ -28,12 +34,53 @@ pub struct Checker {
     pub tile_size: f32,
 }
 
-#[derive(serde::Deserialize, Resource, Clone, Copy)]
+#[derive(serde::Deserialize, Resource, Clone)]
 pub struct GameGameConfig {
     pub start_gold: u64,
     pub start_required_gold: u64,
+    pub turns_per_round: u64,
+    /// Winning this round wins the run, 0 plays forever.
+    pub final_round: u32,
+    /// Replaces `gold_required_multiplier` in endless mode, after a victory.
+    pub endless_gold_required_multiplier: f32,
+    /// Cost of the first paid restock, multiplied by `restock_multiplier` after each.
+    pub restock_cost: f32,
     pub restock_multiplier: f32,
+    /// Restocks that cost nothing, given back at each round.
+    pub free_restocks_per_round: u32,
+    /// Puts the restock cost back to `restock_cost` at each new round.
+    pub reset_restock_cost: bool,
+    pub shop_slots: usize,
+    /// The shop gets one more slot every that many rounds, up to `max_shop_slots`.
+    /// 0 keeps it at `shop_slots`.
+    pub rounds_per_extra_shop_slot: u32,
+    pub max_shop_slots: usize,
     pub gold_required_multiplier: f32,
+    /// Part of the cost given back when selling an actor.
+    pub sell_refund: f32,
+    #[serde(default)]
+    pub activation_order: ActivationOrder,
+    /// End of turn income, see [`IncomeRules`].
+    #[serde(default)]
+    pub income: IncomeRules,
+    /// Shop odds by rarity, each entry applying from its round on.
+    #[serde(default)]
+    pub rarity_weights: Vec<RarityWeights>,
+}
+
+impl GameGameConfig {
+    pub fn shop_rules(&self) -> ShopRules {
+        ShopRules {
+            restock_cost: self.restock_cost,
+            restock_multiplier: self.restock_multiplier,
+            slots: self.shop_slots,
+            max_slots: self.max_shop_slots,
+            rounds_per_extra_slot: self.rounds_per_extra_shop_slot,
+            free_restocks_per_round: self.free_restocks_per_round,
+            reset_restock_cost: self.reset_restock_cost,
+            rarity_weights: self.rarity_weights.clone(),
+        }
+    }
 }
 
 #[derive(serde::Deserialize, Resource, Clone, Copy)]
@@ -67,4 +114,20 @@ pub struct TurnConfig {
     pub complete_push_duration: f32,
     pub cancel_push_duration: f32,
     pub turn_duration: f32,
+    pub spawn_duration: f32,
+    pub despawn_duration: f32,
+    pub overload_duration: f32,
+    /// Past this many commands in a turn, the turn ends with an overload.
+    pub max_commands: usize,
+    /// Past this many activations in a turn, the turn ends with an overload.
+    pub max_activations: usize,
+}
+
+impl TurnConfig {
+    pub fn limits(&self) -> TurnLimits {
+        TurnLimits {
+            max_commands: self.max_commands,
+            max_activations: self.max_activations,
+        }
+    }
 }
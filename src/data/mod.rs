@@ -1,10 +1,15 @@
-use bevy::prelude::*;
+use bevy::{asset::AssetLoadFailedEvent, prelude::*};
 
 use bevy_common_assets::toml::TomlAssetPlugin;
+use errors::DataErrors;
 use game_config::GameConfig;
 
-use crate::{AppSystems, model::actor_types::ActorTypes};
+use crate::{
+    AppSystems,
+    model::{actor_types::ActorTypes, rounds::RoundSchedule},
+};
 
+pub mod errors;
 pub mod game_config;
 
 pub(super) fn plugin(app: &mut App) {
@@ -12,34 +17,113 @@ pub(super) fn plugin(app: &mut App) {
         "config.toml",
     ]));
     app.add_plugins(TomlAssetPlugin::<ActorTypes>::new(&["actor_types.toml"]));
-    app.add_systems(Update, reload_files.in_set(AppSystems::TickTimers));
+    app.add_plugins(TomlAssetPlugin::<RoundSchedule>::new(&["rounds.toml"]));
+    // Until the file is loaded, rounds have no modifiers.
+    app.init_resource::<RoundSchedule>();
+    app.add_systems(
+        Update,
+        (reload_files, report_failed_loads).in_set(AppSystems::TickTimers),
+    );
+    app.add_plugins(errors::plugin);
 }
 
 fn reload_files(
     mut cmd: Commands,
     mut config_asset_events: EventReader<AssetEvent<GameConfig>>,
     mut actor_types_asset_events: EventReader<AssetEvent<ActorTypes>>,
+    mut round_schedule_asset_events: EventReader<AssetEvent<RoundSchedule>>,
     config_asset: Res<Assets<GameConfig>>,
     actor_types_asset: Res<Assets<ActorTypes>>,
+    round_schedule_asset: Res<Assets<RoundSchedule>>,
     asset_server: Res<AssetServer>,
+    mut data_errors: ResMut<DataErrors>,
 ) {
     for ev in config_asset_events.read() {
-        if let AssetEvent::LoadedWithDependencies { .. } = ev {
+        if let AssetEvent::LoadedWithDependencies { id } = ev {
             info!("config loaded.");
-            let (_, config) = config_asset.iter().next().unwrap();
+            let Some(config) = config_asset.get(*id) else {
+                continue;
+            };
+            data_errors.set(asset_path(&asset_server, *id), Vec::new());
             cmd.insert_resource(config.clone());
         }
     }
     for ev in actor_types_asset_events.read() {
-        if let AssetEvent::LoadedWithDependencies { .. } = ev {
+        if let AssetEvent::LoadedWithDependencies { id } = ev {
             info!("actor types loaded.");
-            let (_, actor_types) = actor_types_asset.iter().next().unwrap();
+            let Some(actor_types) = actor_types_asset.get(*id) else {
+                continue;
+            };
+            // Invalid data is reported and not used, so the previous good data stays in place.
+            let errors = actor_types.validate(sprite_exists);
+            let is_valid = errors.is_empty();
+            data_errors.set(
+                asset_path(&asset_server, *id),
+                errors.iter().map(ToString::to_string).collect(),
+            );
+            if !is_valid {
+                continue;
+            }
             let mut actor_types = actor_types.clone();
-            for (_name, actor_type) in actor_types.0.iter_mut() {
+            for (_name, actor_type) in actor_types.types.iter_mut() {
                 actor_type.sprite_handle =
                     Some(asset_server.load(format!("images/{}", actor_type.sprite_name)));
             }
             cmd.insert_resource(actor_types);
         }
     }
+    for ev in round_schedule_asset_events.read() {
+        if let AssetEvent::LoadedWithDependencies { id } = ev {
+            info!("round schedule loaded.");
+            let Some(schedule) = round_schedule_asset.get(*id) else {
+                continue;
+            };
+            let errors = schedule.validate();
+            let is_valid = errors.is_empty();
+            data_errors.set(
+                asset_path(&asset_server, *id),
+                errors.iter().map(ToString::to_string).collect(),
+            );
+            if is_valid {
+                cmd.insert_resource(schedule.clone());
+            }
+        }
+    }
+}
+
+fn report_failed_loads(
+    mut config_failed_events: EventReader<AssetLoadFailedEvent<GameConfig>>,
+    mut actor_types_failed_events: EventReader<AssetLoadFailedEvent<ActorTypes>>,
+    mut round_schedule_failed_events: EventReader<AssetLoadFailedEvent<RoundSchedule>>,
+    mut data_errors: ResMut<DataErrors>,
+) {
+    for ev in config_failed_events.read() {
+        data_errors.set(ev.path.to_string(), vec![ev.error.to_string()]);
+    }
+    for ev in actor_types_failed_events.read() {
+        data_errors.set(ev.path.to_string(), vec![ev.error.to_string()]);
+    }
+    for ev in round_schedule_failed_events.read() {
+        data_errors.set(ev.path.to_string(), vec![ev.error.to_string()]);
+    }
+}
+
+fn asset_path<A: Asset>(asset_server: &AssetServer, id: AssetId<A>) -> String {
+    asset_server
+        .get_path(id)
+        .map_or_else(|| "data".to_string(), |path| path.to_string())
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn sprite_exists(sprite_name: &str) -> bool {
+    bevy::asset::io::file::FileAssetReader::get_base_path()
+        .join("assets/images")
+        .join(sprite_name)
+        .exists()
+}
+
+/// There is no file system to look at on the web, a missing sprite only shows as a missing texture.
+#[cfg(target_family = "wasm")]
+fn sprite_exists(_sprite_name: &str) -> bool {
+    true
 }
@@ -5,8 +5,17 @@ use bevy::{platform::collections::HashMap, prelude::*};
 use crate::{
     AppSystems,
     data::game_config::GameConfig,
-    demo::{Paused, ui::actions::SetActiveActionEvent},
-    model::{actor::ActorId, actor_type::ActorTypeId, direction::Dir, game::Game},
+    demo::{
+        Paused,
+        puff::SpawnHitParticlesEvent,
+        ui::{
+            actions::SetActiveActionEvent, smart_text::UpdateNamedValueEvent,
+            top_bar::UpdateTopBarEvent,
+        },
+    },
+    model::{
+        actor::ActorId, actor_type::ActorTypeId, direction::Dir, game::Game, history::History,
+    },
     screens::Screen,
 };
 
@@ -20,11 +29,13 @@ pub(super) fn plugin(app: &mut App) {
     app.init_resource::<ActorEntities>();
     app.add_observer(on_actor_spawned);
     app.add_observer(on_actor_despawned);
+    app.add_observer(on_despawn_actor);
     app.add_observer(on_spawn_actor);
     app.add_observer(on_actor_rotation_fixup);
+    app.add_systems(Update, keep_tier_badges_upright.in_set(AppSystems::Update));
     app.add_systems(
         Update,
-        (actor_click, update_actions, rotate)
+        (actor_click, update_actions, rotate, sell)
             .run_if(
                 in_state(GameplayState::Placement)
                     .and(in_state(Screen::Gameplay).and(in_state(Paused(false)))),
@@ -75,7 +86,12 @@ pub fn on_actor_rotation_fixup(
 #[derive(Event, Debug, Clone)]
 pub struct ActorRotationFixupEvent;
 
-fn update_actions(mut commands: Commands, hovered_actor: Res<HoveredActor>) {
+fn update_actions(
+    mut commands: Commands,
+    hovered_actor: Res<HoveredActor>,
+    game: Res<Game>,
+    config: Res<GameConfig>,
+) {
     let (_actor_hover, actor_rotatable) = if let Some((_e, actor)) = &**hovered_actor {
         (true, actor.actor_type.rotatable)
     } else {
@@ -85,6 +101,19 @@ fn update_actions(mut commands: Commands, hovered_actor: Res<HoveredActor>) {
         "r_rotate".to_string(),
         actor_rotatable,
     ));
+    let sell_price = (**hovered_actor)
+        .as_ref()
+        .and_then(|(_e, actor)| game.sell_price(&actor.actor_id, config.game.sell_refund));
+    if let Some(sell_price) = sell_price {
+        commands.trigger(UpdateNamedValueEvent {
+            name: "sell_price".to_string(),
+            value: sell_price.to_string(),
+        });
+    }
+    commands.trigger(SetActiveActionEvent(
+        "s_sell".to_string(),
+        sell_price.is_some(),
+    ));
 }
 
 #[derive(Event, Debug, Clone, PartialEq, Eq, Hash)]
@@ -127,6 +156,53 @@ pub fn on_actor_spawned(
             ..default()
         },
     ));
+    if actor_view.actor.tier > 1 {
+        commands
+            .entity(entity)
+            .with_child(tier_badge(actor_view.actor.tier, config.checker.tile_size));
+    }
+}
+
+/// Shows the tier of a merged actor in a corner of its tile.
+#[derive(Component, Debug, Clone, Copy)]
+struct TierBadge {
+    offset: Vec2,
+}
+
+fn tier_badge(tier: u8, tile_size: f32) -> impl Bundle {
+    (
+        Name::new("Tier badge"),
+        TierBadge {
+            offset: Vec2::splat(tile_size * 0.3),
+        },
+        Text2d::new(tier_numeral(tier)),
+        TextFont::from_font_size(tile_size * 0.3),
+        TextColor(Color::srgb(1.0, 0.85, 0.2)),
+        Transform::from_xyz(0.0, 0.0, 1.0),
+    )
+}
+
+pub fn tier_numeral(tier: u8) -> &'static str {
+    match tier {
+        1 => "I",
+        2 => "II",
+        3 => "III",
+        _ => "+",
+    }
+}
+
+/// Badges are children of rotating actors, this undoes the rotation.
+fn keep_tier_badges_upright(
+    q_actors: Query<&Transform, (With<ActorId>, Without<TierBadge>)>,
+    mut q_badges: Query<(&TierBadge, &ChildOf, &mut Transform)>,
+) {
+    for (badge, child_of, mut transform) in &mut q_badges {
+        if let Ok(actor_transform) = q_actors.get(child_of.parent()) {
+            let rotation = actor_transform.rotation.inverse();
+            transform.rotation = rotation;
+            transform.translation = (rotation * badge.offset.extend(0.0)).with_z(1.0);
+        }
+    }
 }
 
 pub fn on_actor_despawned(
@@ -136,7 +212,26 @@ pub fn on_actor_despawned(
 ) {
     let entity = trigger.target();
     let actor_id = q_actor.get(entity).unwrap();
-    actor_entities.0.remove(actor_id);
+    // A newer entity may have taken over that actor already.
+    if actor_entities.get(actor_id) == Some(entity) {
+        actor_entities.0.remove(actor_id);
+    }
+}
+
+/// Despawns the sprite of an actor that is gone from the board.
+/// `ActorEntities` is cleaned up as the entity goes away.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct DespawnActorEvent(pub ActorId);
+
+fn on_despawn_actor(
+    trigger: Trigger<DespawnActorEvent>,
+    mut commands: Commands,
+    actor_entities: Res<ActorEntities>,
+) {
+    let actor_id = trigger.event().0;
+    if let Some(entity) = actor_entities.get(&actor_id) {
+        commands.entity(entity).despawn();
+    }
 }
 
 pub fn on_spawn_actor(
@@ -146,6 +241,13 @@ pub fn on_spawn_actor(
 ) {
     let ev = trigger.event();
     if let Some(actor_id) = game.new_actor(&ev.actor_type_id, ev.coord) {
+        let merged_ids = game.merge_copies(&actor_id);
+        for merged_id in &merged_ids {
+            commands.trigger(DespawnActorEvent(*merged_id));
+        }
+        if !merged_ids.is_empty() {
+            commands.trigger(SpawnHitParticlesEvent(ev.coord));
+        }
         commands.spawn(actor_id);
     } else {
         warn!("could not spawn actor");
@@ -172,15 +274,41 @@ pub fn actor_click(
     }
 }
 
+fn sell(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    hovered_actor: Res<HoveredActor>,
+    mut game: ResMut<Game>,
+    mut history: ResMut<History>,
+    config: Res<GameConfig>,
+) {
+    if input.just_pressed(KeyCode::KeyS) {
+        if let Some((_entity, actor)) = &**hovered_actor {
+            let before = game.clone();
+            if game
+                .sell_actor(&actor.actor_id, config.game.sell_refund)
+                .is_some()
+            {
+                history.record(before);
+                commands.trigger(DespawnActorEvent(actor.actor_id));
+                commands.trigger(SpawnHitParticlesEvent(actor.actor.coord));
+                commands.trigger(UpdateTopBarEvent);
+            }
+        }
+    }
+}
+
 fn rotate(
     mut commands: Commands,
     input: Res<ButtonInput<KeyCode>>,
     hovered_actor: Res<HoveredActor>,
     mut game: ResMut<Game>,
+    mut history: ResMut<History>,
 ) {
     if input.just_pressed(KeyCode::KeyR) {
         if let Some((_entity, actor)) = &**hovered_actor {
             if actor.actor_type.rotatable {
+                history.record(game.clone());
                 game.rotate_actor(&actor.actor_id);
                 commands.trigger(ActorRotationFixupEvent);
             }
@@ -7,7 +7,12 @@ use crate::{
         Paused, actor::ActorRotationFixupEvent, puff::SpawDropParticlesEvent,
         ui::actions::SetActiveActionEvent,
     },
-    model::{actor::ActorId, actor_type::ActorTypeId, game::Game},
+    model::{
+        actor::ActorId,
+        actor_type::ActorTypeId,
+        game::Game,
+        history::{History, Purchase},
+    },
 };
 
 use super::{
@@ -72,11 +77,9 @@ struct Drag {
     can_drop: bool,
 }
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone)]
 pub enum DragSource {
-    Shop {
-        shop_index: usize,
-    },
+    Shop(Box<Purchase>),
     Board {
         dragged_entity: Entity,
         start_coord: IVec2,
@@ -127,7 +130,7 @@ fn on_start_drag(
     if let Some(actor_type) = game.actor_types().get(&ev.actor_type_id) {
         commands.insert_resource(Drag {
             actor_type_id: ev.actor_type_id.clone(),
-            source: ev.source,
+            source: ev.source.clone(),
             can_drop: false,
         });
         next_state.set(GameplayState::Drag);
@@ -149,8 +152,8 @@ fn on_start_drag(
                 tile_coord_to_world_coord(coord, tile_size).extend(3.0),
             ));
         }
-        if let DragSource::Board { dragged_entity, .. } = ev.source {
-            if let Ok((mut sprite, _actor_id)) = q_sprite.get_mut(dragged_entity) {
+        if let DragSource::Board { dragged_entity, .. } = &ev.source {
+            if let Ok((mut sprite, _actor_id)) = q_sprite.get_mut(*dragged_entity) {
                 sprite.color = Color::linear_rgba(1.0, 1.0, 1.0, config.drag.alpha);
             }
         }
@@ -210,9 +213,9 @@ fn on_cancel_drag(
     mut q_actor_sprite: Query<&mut Sprite, With<ActorId>>,
 ) {
     // warn!("on_cancel_drag");
-    match drag.source {
-        DragSource::Shop { shop_index } => {
-            game.return_item(&drag.actor_type_id, shop_index);
+    match &drag.source {
+        DragSource::Shop(purchase) => {
+            purchase.cancel(&mut game);
             next_state.set(GameplayState::Placement);
         }
         DragSource::Board { .. } => {
@@ -235,12 +238,14 @@ fn on_drop(
     mut q_tr: Query<&mut Transform, With<ActorId>>,
     mut q_actor_sprite: Query<&mut Sprite, With<ActorId>>,
     config: Res<GameConfig>,
+    mut history: ResMut<History>,
 ) {
     // warn!("on_drop");
-    match drag.source {
-        DragSource::Shop { .. } => {
+    match &drag.source {
+        DragSource::Shop(purchase) => {
             if let Some(coord) = &**hovered_tile_coord {
                 if drag.can_drop {
+                    purchase.place(&mut history);
                     commands.trigger(SpawnActorEvent {
                         actor_type_id: drag.actor_type_id.clone(),
                         coord: *coord,
@@ -250,13 +255,14 @@ fn on_drop(
                 }
             }
         }
-        DragSource::Board {
+        &DragSource::Board {
             dragged_entity,
             start_coord,
         } => {
             if let Some(target_coord) = **hovered_tile_coord {
                 if drag.can_drop {
                     commands.trigger(SpawDropParticlesEvent(target_coord));
+                    history.record(game.clone());
                     game.swap_coords(start_coord, target_coord);
                     if let Some((target_entity, _actor_view)) = &**hovered_actor {
                         *q_tr.get_mut(*target_entity).unwrap() = Transform::from_translation(
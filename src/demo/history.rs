@@ -0,0 +1,65 @@
+//! Undo and redo of placement-phase actions.
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::{
+        GameplayState, level::ResetBoardEvent, replay::Replay, ui::actions::SetActiveActionEvent,
+        ui::top_bar::UpdateTopBarEvent,
+    },
+    model::{game::Game, history::History},
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<History>();
+    app.add_systems(OnEnter(Screen::Gameplay), clear_history);
+    // A replay leaves the run as it was, so its history still holds.
+    app.add_systems(
+        OnEnter(GameplayState::Turn),
+        clear_history.run_if(not(resource_exists::<Replay>)),
+    );
+    app.add_systems(
+        Update,
+        (update_actions, undo_redo)
+            .run_if(in_state(GameplayState::Placement).and(in_state(Screen::Gameplay))),
+    );
+}
+
+fn clear_history(mut history: ResMut<History>) {
+    history.clear();
+}
+
+fn update_actions(mut commands: Commands, history: Res<History>) {
+    commands.trigger(SetActiveActionEvent(
+        "ctrl_z_undo".to_string(),
+        history.can_undo(),
+    ));
+    commands.trigger(SetActiveActionEvent(
+        "ctrl_y_redo".to_string(),
+        history.can_redo(),
+    ));
+}
+
+fn undo_redo(
+    mut commands: Commands,
+    keys: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<History>,
+    mut game: ResMut<Game>,
+) {
+    if !keys.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let state = if keys.just_pressed(KeyCode::KeyZ) {
+        history.undo(&game)
+    } else if keys.just_pressed(KeyCode::KeyY) {
+        history.redo(&game)
+    } else {
+        None
+    };
+    if let Some(state) = state {
+        *game = state;
+        commands.trigger(ResetBoardEvent);
+        commands.trigger(UpdateTopBarEvent);
+    }
+}
@@ -10,9 +10,11 @@ use crate::{
     demo::{
         GameplayState,
         // music::{Song, ToSongEvent},
-        ui::actions::SetActiveActionEvent,
+        replay::Replay,
+        ui::{actions::SetActiveActionEvent, round_start::RoundStartEvent},
     },
-    model::{actor::ActorId, actor_types::ActorTypes, game::Game},
+    model::{actor::ActorId, actor_types::ActorTypes, game::Game, rounds::RoundSchedule},
+    persistence::PendingSave,
     screens::Screen,
 };
 
@@ -140,12 +142,32 @@ impl FromWorld for LevelAssets {
     }
 }
 
-pub fn enter(mut commands: Commands, actor_types: Res<ActorTypes>, game_config: Res<GameConfig>) {
-    let mut game = Game::new(&game_config.game, actor_types.clone());
-    game.restock();
-    let start_actor_id = game.board().start_actor_id();
+pub fn enter(
+    mut commands: Commands,
+    actor_types: Res<ActorTypes>,
+    game_config: Res<GameConfig>,
+    schedule: Res<RoundSchedule>,
+    pending_save: Option<Res<PendingSave>>,
+    replay: Option<Res<Replay>>,
+) {
+    let game = if let Some(pending_save) = pending_save {
+        commands.remove_resource::<PendingSave>();
+        commands.trigger(RoundStartEvent);
+        pending_save.0.clone().into_game(actor_types.clone())
+    } else if let Some(replay) = replay.filter(|replay| replay.viewer) {
+        replay.turn.start_game(actor_types.clone())
+    } else {
+        let mut game = Game::new(&game_config.game, actor_types.clone());
+        game.start_round(&schedule);
+        game.restock();
+        commands.trigger(RoundStartEvent);
+        game
+    };
+    let actor_ids: Vec<ActorId> = game.board().actor_ids().copied().collect();
     commands.insert_resource(game);
-    commands.spawn(start_actor_id);
+    for actor_id in actor_ids {
+        commands.spawn(actor_id);
+    }
     // commands.trigger(ToSongEvent(Song::Song1));
 }
 
@@ -2,11 +2,13 @@ pub mod actor;
 mod camera;
 pub mod drag;
 pub mod follow;
+pub mod history;
 pub mod level;
 mod mouse;
 pub mod music;
 pub mod particle;
 pub mod puff;
+pub mod replay;
 pub mod sprite_animate;
 pub mod tile;
 pub mod turn;
@@ -28,10 +30,12 @@ pub(super) fn plugin(app: &mut App) {
         ui::plugin,
         drag::plugin,
         puff::plugin,
+        replay::plugin,
         sprite_animate::plugin,
         particle::plugin,
         turn::plugin,
         follow::plugin,
+        history::plugin,
         music::plugin,
     ));
     // app.add_systems(OnEnter(Paused(true)), enter_paused);
@@ -57,6 +61,7 @@ pub enum GameplayState {
     Turn,
     Drag,
     GameOver,
+    Victory,
     // Run,
 }
 
@@ -0,0 +1,120 @@
+//! Plays recorded turns back through the turn pipeline, without touching the run.
+
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
+
+use crate::{
+    demo::{GameplayState, ui::actions::SetActiveActionEvent, ui::smart_text::SmartText},
+    model::{
+        game::Game,
+        replay::{RunLog, TurnRecord},
+    },
+    screens::Screen,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<RunLog>();
+    app.add_systems(OnEnter(Screen::Gameplay), clear_run_log);
+    app.add_systems(OnExit(Screen::Gameplay), stop_replay);
+    app.add_systems(
+        Update,
+        (
+            update_actions,
+            replay_last_turn.run_if(input_just_pressed(KeyCode::KeyL)),
+        )
+            .run_if(in_state(GameplayState::Placement).and(in_state(Screen::Gameplay))),
+    );
+    #[cfg(not(target_family = "wasm"))]
+    app.add_systems(
+        Update,
+        export_last_turn.run_if(
+            in_state(GameplayState::Placement)
+                .and(in_state(Screen::Gameplay))
+                .and(input_just_pressed(KeyCode::KeyX)),
+        ),
+    );
+    app.add_systems(
+        OnEnter(GameplayState::Turn),
+        spawn_replay_banner.run_if(resource_exists::<Replay>),
+    );
+}
+
+/// A recorded turn, played instead of a real one.
+#[derive(Resource, Debug, Clone)]
+pub struct Replay {
+    pub turn: TurnRecord,
+    /// Loaded from a file in the main menu, so there is no run to go back to.
+    pub viewer: bool,
+}
+
+/// The real game, put aside while a replay plays.
+#[derive(Resource, Debug, Clone)]
+pub struct StashedGame(pub Game);
+
+fn clear_run_log(mut run_log: ResMut<RunLog>) {
+    run_log.clear();
+}
+
+/// Leaving mid-replay must not turn the next game into a replay.
+fn stop_replay(mut commands: Commands) {
+    commands.remove_resource::<Replay>();
+    commands.remove_resource::<StashedGame>();
+}
+
+fn update_actions(mut commands: Commands, run_log: Res<RunLog>) {
+    let has_turn = run_log.last_turn().is_some();
+    commands.trigger(SetActiveActionEvent("l_replay".to_string(), has_turn));
+    commands.trigger(SetActiveActionEvent(
+        "x_export_replay".to_string(),
+        has_turn && !cfg!(target_family = "wasm"),
+    ));
+}
+
+fn replay_last_turn(
+    mut commands: Commands,
+    run_log: Res<RunLog>,
+    mut next_state: ResMut<NextState<GameplayState>>,
+) {
+    if let Some(turn) = run_log.last_turn() {
+        commands.insert_resource(Replay {
+            turn: turn.clone(),
+            viewer: false,
+        });
+        next_state.set(GameplayState::Turn);
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn export_last_turn(run_log: Res<RunLog>) {
+    use crate::{model::replay::ReplayFile, persistence};
+
+    let Some(turn) = run_log.last_turn() else {
+        return;
+    };
+    if let Some(path) = persistence::write_replay(&ReplayFile::new(turn)) {
+        info!("replay written to {}", path.display());
+    }
+}
+
+fn spawn_replay_banner(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Replay Banner"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(90.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        GlobalZIndex(5),
+        Pickable::IGNORE,
+        StateScoped(GameplayState::Turn),
+        children![(
+            Node {
+                padding: UiRect::axes(Val::Px(20.), Val::Px(5.0)),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+            SmartText::new("Replay", 25.),
+        )],
+    ));
+}
@@ -1,10 +1,10 @@
 use std::time::Duration;
 
-use bevy::prelude::*;
+use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 use bevy_tween::{
     bevy_time_runner::{TimeRunner, TimeRunnerEnded},
     combinator::{event, forward, sequence, tween},
-    interpolate::translation,
+    interpolate::{scale, translation},
     prelude::{AnimationBuilderExt, EaseKind, Repeat, RepeatStyle, TweenEvent},
     tween::{AnimationTarget, TargetComponent},
     tween_event::TweenEventPlugin,
@@ -20,15 +20,22 @@ use crate::{
         follow::{Follows, follow_offset},
         level::{LevelAssets, ResetBoardEvent},
         puff::SpawnHitParticlesEvent,
+        replay::{Replay, StashedGame},
         tile::tile_coord_to_world_coord,
-        ui::{game_over::GameOverEvent, top_bar::UpdateTopBarEvent},
+        ui::{
+            game_over::GameOverEvent, overload::OverloadEvent, round_start::RoundStartEvent,
+            top_bar::UpdateTopBarEvent, victory::VictoryEvent,
+        },
     },
     model::{
-        actor::ActorId,
+        actor::{Actor, ActorId},
         direction::RelDir,
         game::Game,
+        replay::RunLog,
+        rounds::RoundSchedule,
         runner::{Cmd, Dest, Runner},
     },
+    screens::Screen,
 };
 
 pub(super) fn plugin(app: &mut App) {
@@ -44,17 +51,49 @@ pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(TurnState::EndTurn), enter_end_turn);
     app.add_systems(Update, on_time_runner_ended);
 
+    app.init_resource::<StepDebug>();
+    app.add_systems(
+        Update,
+        toggle_step_debug.run_if(in_state(Screen::Gameplay).and(input_just_pressed(KeyCode::F8))),
+    );
+    app.add_systems(
+        Update,
+        step.run_if(
+            in_state(TurnState::WorkaroundBugs)
+                .and(in_state(Paused(false)))
+                .and(input_just_pressed(KeyCode::KeyN)),
+        ),
+    );
+
+    app.init_resource::<PlaybackSpeed>();
+    app.add_systems(
+        Update,
+        (
+            (
+                set_playback_speed,
+                skip_to_end.run_if(input_just_pressed(KeyCode::Enter)),
+            )
+                .run_if(in_state(TurnState::WorkaroundBugs).and(in_state(Paused(false)))),
+            apply_playback_speed,
+        )
+            .chain()
+            .run_if(in_state(GameplayState::Turn)),
+    );
+
     app.add_observer(on_spawn_activation);
     app.add_observer(on_despawn_activation);
     app.add_observer(on_move_actor);
     app.add_observer(on_fail_move_actor);
     app.add_observer(on_hit);
     app.add_observer(on_need_command);
+    app.add_observer(on_play_next_command);
     app.add_observer(on_try_push);
     app.add_observer(on_complete_push);
     app.add_observer(on_cancel_push);
     app.add_observer(on_prize);
     app.add_observer(on_turn);
+    app.add_observer(on_spawn);
+    app.add_observer(on_despawn);
 
     app.add_observer(on_anim_event);
 }
@@ -64,6 +103,7 @@ enum AnimEvent {
     Hit(IVec2),
     AnimDone,
     PlaySfx(Handle<AudioSource>),
+    Despawn(Entity),
     #[default]
     None,
 }
@@ -71,6 +111,37 @@ enum AnimEvent {
 #[derive(Resource, Debug, Default)]
 struct Cmds(Vec<Cmd>, Game);
 
+/// Step-by-step mode, for designing actor types: the turn waits for a key press
+/// before every command.
+#[derive(Resource, Debug, Default)]
+pub struct StepDebug {
+    pub enabled: bool,
+    waiting: bool,
+    /// The command being played.
+    pub current: Option<Cmd>,
+    /// Rebuilt from the commands, oldest activation first.
+    pub activation_stack: Vec<ActorId>,
+}
+
+/// Every tween and delay of a turn runs this many times faster.
+#[derive(Resource, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PlaybackSpeed {
+    #[default]
+    Normal,
+    Double,
+    Quadruple,
+}
+
+impl PlaybackSpeed {
+    pub fn time_scale(self) -> f32 {
+        match self {
+            PlaybackSpeed::Normal => 1.0,
+            PlaybackSpeed::Double => 2.0,
+            PlaybackSpeed::Quadruple => 4.0,
+        }
+    }
+}
+
 impl Cmds {
     fn pop(&mut self) -> Option<Cmd> {
         self.0.pop()
@@ -104,6 +175,10 @@ struct HitEvent(ActorId, IVec2);
 #[derive(Event, Debug)]
 struct NeedCommandEvent;
 
+/// Pops and plays the next command right away, even in step mode.
+#[derive(Event, Debug)]
+struct PlayNextCommandEvent;
+
 #[derive(Event, Debug)]
 struct TryPushEvent(ActorId, IVec2);
 
@@ -120,24 +195,114 @@ struct TurnEvent(ActorId, RelDir);
 #[allow(dead_code)]
 struct PrizeEvent(ActorId, u64);
 
-fn enter(mut commands: Commands, game: Res<Game>) {
+#[derive(Event, Debug)]
+struct SpawnEvent(ActorId, Actor);
+
+#[derive(Event, Debug)]
+struct DespawnEvent(ActorId);
+
+fn enter(
+    mut commands: Commands,
+    mut game: ResMut<Game>,
+    config: Res<GameConfig>,
+    mut run_log: ResMut<RunLog>,
+    mut step_debug: ResMut<StepDebug>,
+    replay: Option<Res<Replay>>,
+) {
+    step_debug.current = None;
+    step_debug.activation_stack.clear();
+    if let Some(replay) = replay {
+        warn!("start replay!");
+        commands.insert_resource(StashedGame(game.clone()));
+        *game = replay.turn.start_game(game.actor_types().clone());
+        let mut result = replay.turn.cmds.clone();
+        result.reverse();
+        commands.insert_resource(Cmds(result, game.clone()));
+        commands.trigger(ResetBoardEvent);
+        commands.trigger(UpdateTopBarEvent);
+        // leaves a frame for the board entities to be spawned
+        done_in(commands.reborrow(), 0.0);
+        return;
+    }
     warn!("start running!");
     let runner_game = game.clone();
     // warn!("board before running: {:#?}", runner_game.board());
-    let mut runner = Runner::new(runner_game);
+    let mut runner = Runner::configured(runner_game, &config);
     let (new_game, mut result) = runner.run();
     // warn!("board after running: {:#?}", new_game.board());
     warn!("done running!\n{result:#?}");
+    run_log.record(game.clone(), result.clone());
     result.reverse();
     commands.insert_resource(Cmds(result, new_game));
     commands.trigger(NeedCommandEvent);
 }
 
-fn exit(mut command: Commands, mut set_state: ResMut<NextState<TurnState>>) {
+fn exit(
+    mut command: Commands,
+    mut set_state: ResMut<NextState<TurnState>>,
+    mut step_debug: ResMut<StepDebug>,
+) {
     command.remove_resource::<Cmds>();
+    step_debug.waiting = false;
     set_state.set(TurnState::WorkaroundBugs);
 }
 
+fn toggle_step_debug(mut commands: Commands, mut step_debug: ResMut<StepDebug>) {
+    step_debug.enabled = !step_debug.enabled;
+    if !step_debug.enabled && step_debug.waiting {
+        step_debug.waiting = false;
+        commands.trigger(PlayNextCommandEvent);
+    }
+}
+
+fn step(mut commands: Commands, mut step_debug: ResMut<StepDebug>) {
+    if step_debug.waiting {
+        step_debug.waiting = false;
+        commands.trigger(PlayNextCommandEvent);
+    }
+}
+
+fn set_playback_speed(keys: Res<ButtonInput<KeyCode>>, mut speed: ResMut<PlaybackSpeed>) {
+    let new_speed = if keys.just_pressed(KeyCode::Digit1) {
+        PlaybackSpeed::Normal
+    } else if keys.just_pressed(KeyCode::Digit2) {
+        PlaybackSpeed::Double
+    } else if keys.just_pressed(KeyCode::Digit3) {
+        PlaybackSpeed::Quadruple
+    } else {
+        return;
+    };
+    speed.set_if_neq(new_speed);
+}
+
+fn apply_playback_speed(speed: Res<PlaybackSpeed>, mut q_time_runner: Query<&mut TimeRunner>) {
+    let time_scale = speed.time_scale();
+    for mut time_runner in &mut q_time_runner {
+        if time_runner.time_scale() != time_scale {
+            time_runner.set_time_scale(time_scale);
+        }
+    }
+}
+
+/// Drops the remaining commands and their animations, and jumps to the state
+/// the runner ended the turn in, prizes included.
+fn skip_to_end(
+    mut commands: Commands,
+    mut cmds: ResMut<Cmds>,
+    mut game: ResMut<Game>,
+    q_animations: Query<Entity, Or<(With<TimeRunner>, With<ActivationSprite>)>>,
+    mut set_state: ResMut<NextState<TurnState>>,
+) {
+    cmds.0.clear();
+    for entity in &q_animations {
+        commands.entity(entity).try_despawn();
+    }
+    *game = cmds.1.clone();
+    commands.trigger(ResetBoardEvent);
+    commands.trigger(UpdateTopBarEvent);
+    set_state.set(TurnState::EndTurn);
+}
+
 fn enter_pause(mut q_time_runner: Query<&mut TimeRunner>) {
     for mut time_runner in &mut q_time_runner {
         time_runner.set_paused(true);
@@ -153,21 +318,47 @@ fn exit_pause(mut q_time_runner: Query<&mut TimeRunner>) {
 fn enter_end_turn(
     mut next_turn_state: ResMut<NextState<TurnState>>,
     mut next_gameplay_state: ResMut<NextState<GameplayState>>,
+    mut next_screen: ResMut<NextState<Screen>>,
     cmds: Res<Cmds>,
     mut game: ResMut<Game>,
     mut commands: Commands,
+    replay: Option<Res<Replay>>,
+    stashed_game: Option<Res<StashedGame>>,
+    schedule: Res<RoundSchedule>,
 ) {
+    if let Some(replay) = replay {
+        commands.remove_resource::<Replay>();
+        commands.remove_resource::<StashedGame>();
+        next_turn_state.set(TurnState::WorkaroundBugs);
+        if replay.viewer {
+            next_gameplay_state.set(GameplayState::WorkaroundBugs);
+            next_screen.set(Screen::Title);
+        } else {
+            if let Some(stashed_game) = stashed_game {
+                *game = stashed_game.0.clone();
+            }
+            next_gameplay_state.set(GameplayState::Placement);
+            commands.trigger(ResetBoardEvent);
+            commands.trigger(UpdateTopBarEvent);
+        }
+        return;
+    }
     *game = cmds.1.clone();
+    game.end_turn();
+    commands.trigger(UpdateTopBarEvent);
 
     let game_over = game.is_round_end() && !game.can_go_next_round();
     if game_over {
         commands.trigger(GameOverEvent);
+    } else if game.is_victory() {
+        commands.trigger(VictoryEvent);
     } else {
         next_turn_state.set(TurnState::WorkaroundBugs);
         next_gameplay_state.set(GameplayState::Placement);
         commands.trigger(ResetBoardEvent);
         if game.is_round_end() {
-            game.next_round();
+            game.next_round(&schedule);
+            commands.trigger(RoundStartEvent);
         }
     }
 }
@@ -175,17 +366,34 @@ fn enter_end_turn(
 fn on_need_command(
     _trigger: Trigger<NeedCommandEvent>,
     mut commands: Commands,
+    mut step_debug: ResMut<StepDebug>,
+) {
+    if step_debug.enabled {
+        step_debug.waiting = true;
+    } else {
+        commands.trigger(PlayNextCommandEvent);
+    }
+}
+
+fn on_play_next_command(
+    _trigger: Trigger<PlayNextCommandEvent>,
+    mut commands: Commands,
     mut cmds: ResMut<Cmds>,
     mut set_state: ResMut<NextState<TurnState>>,
+    mut step_debug: ResMut<StepDebug>,
+    config: Res<GameConfig>,
 ) {
     if let Some(cmd) = cmds.pop() {
+        step_debug.current = Some(cmd.clone());
         match cmd {
             Cmd::Activate(actor_id) => {
                 warn!("activation");
+                step_debug.activation_stack.push(actor_id);
                 commands.trigger(SpawnActivation(actor_id));
             }
             Cmd::Deactivate(actor_id) => {
                 warn!("deactivation");
+                step_debug.activation_stack.retain(|id| *id != actor_id);
                 commands.trigger(DespawnActivation(actor_id));
             }
             Cmd::MoveTo(Ok(dest)) => {
@@ -227,6 +435,19 @@ fn on_need_command(
                 warn!("Cmd::Prize {gold}");
                 commands.trigger(PrizeEvent(actor_id, gold));
             }
+            Cmd::Spawn(actor_id, actor) => {
+                warn!("Cmd::Spawn");
+                commands.trigger(SpawnEvent(actor_id, actor));
+            }
+            Cmd::Despawn(actor_id) => {
+                warn!("Cmd::Despawn");
+                commands.trigger(DespawnEvent(actor_id));
+            }
+            Cmd::Overload(reason) => {
+                warn!("Cmd::Overload {reason:?}");
+                commands.trigger(OverloadEvent(reason));
+                done_in(commands.reborrow(), config.turn.overload_duration);
+            }
         }
     } else {
         warn!("no more commands");
@@ -590,6 +811,67 @@ fn on_cancel_push(
     }
 }
 
+fn on_spawn(
+    trigger: Trigger<SpawnEvent>,
+    mut commands: Commands,
+    mut game: ResMut<Game>,
+    config: Res<GameConfig>,
+) {
+    let SpawnEvent(actor_id, actor) = trigger.event();
+    if !game.insert_actor(*actor_id, actor.clone()) {
+        warn!("could not spawn {actor_id:?}");
+        done_in(commands.reborrow(), 0.0);
+        return;
+    }
+    commands.trigger(CameraToActorEvent(*actor_id));
+    commands.trigger(SpawnHitParticlesEvent(actor.coord));
+    // the actor observer fills in the sprite and translation
+    let actor_entity = commands
+        .spawn((*actor_id, Transform::from_scale(Vec3::ZERO)))
+        .id();
+    commands.animation().insert(sequence((
+        tween(
+            Duration::from_secs_f32(config.turn.spawn_duration),
+            EaseKind::BackOut,
+            TargetComponent::entity(actor_entity).with(scale(Vec3::ZERO, Vec3::ONE)),
+        ),
+        event(AnimEvent::AnimDone),
+    )));
+}
+
+fn on_despawn(
+    trigger: Trigger<DespawnEvent>,
+    mut commands: Commands,
+    mut game: ResMut<Game>,
+    config: Res<GameConfig>,
+    actor_entities: Res<ActorEntities>,
+    assets: Res<LevelAssets>,
+) {
+    let actor_id = trigger.event().0;
+    let (Some(actor), Some(actor_entity)) =
+        (game.remove_actor(&actor_id), actor_entities.get(&actor_id))
+    else {
+        done_in(commands.reborrow(), 0.0);
+        return;
+    };
+    commands.trigger(SpawnHitParticlesEvent(actor.coord));
+    // not a child of the actor, so that it outlives it
+    commands.animation().insert(sequence((
+        tween(
+            Duration::from_secs_f32(config.turn.despawn_duration),
+            EaseKind::BackIn,
+            TargetComponent::entity(actor_entity).with(scale(Vec3::ONE, Vec3::ZERO)),
+        ),
+        event(AnimEvent::Despawn(actor_entity)),
+        event(AnimEvent::AnimDone),
+    )));
+    anim_event_in(
+        commands.reborrow(),
+        0.0,
+        AnimEvent::PlaySfx(assets.cancel_push_sfx.clone()),
+    );
+}
+
 fn on_anim_event(
     trigger: Trigger<TweenEvent<AnimEvent>>,
     mut commands: Commands,
@@ -603,6 +885,9 @@ fn on_anim_event(
         AnimEvent::PlaySfx(ref handle) => {
             commands.spawn(sound_effect(handle.clone()));
         }
+        AnimEvent::Despawn(entity) => {
+            commands.entity(entity).despawn();
+        }
         AnimEvent::None => unreachable!(),
     }
 }
@@ -1,4 +1,4 @@
-use bevy::{platform::collections::HashMap, prelude::*};
+use bevy::{ecs::spawn::SpawnIter, platform::collections::HashMap, prelude::*};
 
 use crate::{
     demo::{GameplayState, ui::smart_text::SmartText},
@@ -8,7 +8,7 @@ use crate::{
 pub(super) fn plugin(app: &mut App) {
     // app.add_systems(OnEnter(Screen::Gameplay), bleh)
     let mut actions = HashMap::new();
-    for action in ALL_ACTIONS {
+    for (action, _hint) in ACTION_HINTS {
         actions.insert(action.to_string(), false);
     }
     app.insert_resource(ActiveActions(actions));
@@ -28,15 +28,21 @@ pub struct SetActiveActionEvent(pub String, pub bool);
 #[derive(Resource, Debug, Default)]
 struct ActiveActions(HashMap<String, bool>);
 
-const ALL_ACTIONS: [&str; 8] = [
-    "mmb_pan",
-    "lmb_mmb_pan",
-    "mmb_zoom",
-    "lmb_drag",
-    "lmb_drop",
-    "rmb_cancel_drag",
-    "r_rotate",
-    "start_turn",
+/// Action names, with the hint shown while they are active.
+const ACTION_HINTS: [(&str, &str); 13] = [
+    ("mmb_pan", "{icon:mmb} Pan"),
+    ("lmb_mmb_pan", "{icon:lmb}{icon:mmb} Pan"),
+    ("mmb_zoom", "{icon:mmb} Zoom"),
+    ("lmb_drag", "{icon:lmb} Drag"),
+    ("lmb_drop", "{icon:lmb} Drop"),
+    ("rmb_cancel_drag", "{icon:rmb} Cancel drag"),
+    ("r_rotate", "{icon:rotate} Rotate"),
+    ("s_sell", "S Sell for {named:sell_price}{icon:coin}"),
+    ("start_turn", "{icon:turn}{icon:lmb} Start turn"),
+    ("l_replay", "L Replay last turn"),
+    ("x_export_replay", "X Export replay"),
+    ("ctrl_z_undo", "Ctrl+Z Undo"),
+    ("ctrl_y_redo", "Ctrl+Y Redo"),
 ];
 
 fn on_set_active_action(
@@ -83,101 +89,26 @@ fn enter(mut commands: Commands) {
             flex_wrap: FlexWrap::Wrap,
             ..default()
         },
-        children![
-            (
-                Name::new("mmb_pan"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:mmb} Pan".to_string()
-                }
-            ),
-            (
-                Name::new("lmb_mmb_pan"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:lmb}{icon:mmb} Pan".to_string()
-                }
-            ),
-            (
-                Name::new("mmb_zoom"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:mmb} Zoom".to_string()
-                }
-            ),
-            (
-                Name::new("lmb_drag"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:lmb} Drag".to_string()
-                }
-            ),
-            (
-                Name::new("lmb_drop"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:lmb} Drop".to_string()
-                }
-            ),
-            (
-                Name::new("rmb_cancel_drag"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:rmb} Cancel drag".to_string()
-                }
-            ),
-            (
-                Name::new("r_rotate"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:rotate} Rotate".to_string()
-                }
-            ),
-            (
-                Name::new("start_turn"),
-                Node {
-                    margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
-                    ..default()
-                },
-                SmartText {
-                    font_size,
-                    text: "{icon:turn}{icon:lmb} Start turn".to_string()
-                }
-            )
-        ],
+        Children::spawn(SpawnIter(ACTION_HINTS.into_iter().map(
+            move |(name, hint)| {
+                (
+                    Name::new(name),
+                    Node {
+                        margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
+                        ..default()
+                    },
+                    SmartText {
+                        font_size,
+                        text: hint.to_string(),
+                    },
+                )
+            },
+        ))),
     ));
 }
 
 fn exit(mut cmd: Commands) {
-    for action in ALL_ACTIONS {
+    for (action, _hint) in ACTION_HINTS {
         cmd.trigger(SetActiveActionEvent(action.to_string(), false));
     }
 }
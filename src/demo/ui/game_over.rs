@@ -35,19 +35,40 @@ fn on_game_over(
         center_ui_root("End turn Ui"),
         GlobalZIndex(6),
         StateScoped(GameplayState::GameOver),
-        children![window()],
+        children![summary_window(
+            "Game Over.",
+            COMMENTS[rand::random::<usize>() % COMMENTS.len()],
+            "But don't worry ! You made {named:total_gold}{icon:coin} and reached round {named:round}{icon:round}.",
+            buttons(),
+        )],
     ));
-    commands.trigger(UpdateNamedValueEvent {
-        name: "total_gold".to_string(),
-        value: game.total_gold().to_string(),
-    });
-    commands.trigger(UpdateNamedValueEvent {
-        name: "round".to_string(),
-        value: game.total_gold().to_string(),
-    });
+    update_run_stats(&mut commands, &game);
+}
+
+/// Sets the named values shown by [`summary_window`].
+pub(super) fn update_run_stats(commands: &mut Commands, game: &Game) {
+    let stats = game.stats();
+    for (name, value) in [
+        ("total_gold", game.total_gold().to_string()),
+        ("round", game.round().to_string()),
+        ("turns_played", stats.turns_played.to_string()),
+        ("best_turn_gold", stats.best_turn_gold.to_string()),
+        ("actors_bought", stats.actors_bought.to_string()),
+    ] {
+        commands.trigger(UpdateNamedValueEvent {
+            name: name.to_string(),
+            value,
+        });
+    }
 }
 
-fn window() -> impl Bundle {
+/// The end of run window, shared by the game over and the victory.
+pub(super) fn summary_window(
+    title_text: &str,
+    comment_text: &str,
+    stats_text: &str,
+    buttons: impl Bundle,
+) -> impl Bundle {
     (
         Node {
             flex_direction: FlexDirection::Column,
@@ -58,11 +79,15 @@ fn window() -> impl Bundle {
             ..default()
         },
         BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
-        children![title(), content(), buttons()],
+        children![
+            title(title_text),
+            content(comment_text, stats_text),
+            buttons
+        ],
     )
 }
 
-fn title() -> impl Bundle {
+fn title(text: &str) -> impl Bundle {
     (
         Node {
             flex_direction: FlexDirection::Column,
@@ -82,7 +107,7 @@ fn title() -> impl Bundle {
                 // width: Val::Px(100.0),
                 ..default()
             },
-            SmartText::new("Game Over.", 30.)
+            SmartText::new(text, 30.)
         )],
     )
 }
@@ -95,7 +120,7 @@ const COMMENTS: [&str; 5] = [
     "I'm so disapointed...",
 ];
 
-fn content() -> impl Bundle {
+fn content(comment_text: &str, stats_text: &str) -> impl Bundle {
     (
         Node {
             flex_direction: FlexDirection::Column,
@@ -105,21 +130,21 @@ fn content() -> impl Bundle {
             ..default()
         },
         BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
-        children![comment(), game_stats()],
+        children![comment(comment_text), game_stats(stats_text)],
     )
 }
 
-fn comment() -> impl Bundle {
+fn comment(text: &str) -> impl Bundle {
     (
         Node {
             margin: UiRect::axes(Val::Px(20.), Val::Px(10.0)),
             ..default()
         },
-        SmartText::new(COMMENTS[rand::random::<usize>() % COMMENTS.len()], 20.),
+        SmartText::new(text, 20.),
     )
 }
 
-fn game_stats() -> impl Bundle {
+fn game_stats(text: &str) -> impl Bundle {
     (
         Node {
             flex_direction: FlexDirection::Column,
@@ -129,16 +154,25 @@ fn game_stats() -> impl Bundle {
             ..default()
         },
         // BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
-        children![(
-            Node {
-                margin: UiRect::axes(Val::Px(20.), Val::Px(10.0)),
-                ..default()
-            },
-            SmartText::new(
-                "But don't worry ! You made {named:total_gold}{icon:coin} and reached round {named:round}{icon:round}.",
-                20.
+        children![
+            (
+                Node {
+                    margin: UiRect::axes(Val::Px(20.), Val::Px(10.0)),
+                    ..default()
+                },
+                SmartText::new(text, 20.)
+            ),
+            (
+                Node {
+                    margin: UiRect::axes(Val::Px(20.), Val::Px(10.0)),
+                    ..default()
+                },
+                SmartText::new(
+                    "{named:turns_played}{icon:turn} played, best turn {named:best_turn_gold}{icon:coin}, {named:actors_bought} actors bought.",
+                    16.
+                )
             )
-        )],
+        ],
     )
 }
 
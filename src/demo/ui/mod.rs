@@ -1,10 +1,15 @@
 pub mod actions;
 pub mod end_turn;
 pub mod game_over;
+pub mod overload;
+pub mod playback;
+pub mod round_start;
 pub mod shop;
 pub mod smart_text;
 pub mod startup_bar;
+pub mod step_panel;
 pub mod top_bar;
+pub mod victory;
 
 use bevy::prelude::*;
 use top_bar::top_bar_ui;
@@ -19,8 +24,13 @@ pub(super) fn plugin(app: &mut App) {
         smart_text::plugin,
         actions::plugin,
         startup_bar::plugin,
+        step_panel::plugin,
         end_turn::plugin,
         game_over::plugin,
+        overload::plugin,
+        playback::plugin,
+        round_start::plugin,
+        victory::plugin,
     ));
     // app.add_observer(on_shop_button_clicked);
 }
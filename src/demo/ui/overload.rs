@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+
+use crate::{
+    demo::{GameplayState, ui::smart_text::SmartText},
+    model::runner::OverloadReason,
+    theme::widget::center_ui_root,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(on_overload);
+}
+
+/// The runner cut the turn short; tells the player why.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct OverloadEvent(pub OverloadReason);
+
+fn on_overload(trigger: Trigger<OverloadEvent>, mut commands: Commands) {
+    commands.spawn((
+        center_ui_root("Overload Ui"),
+        GlobalZIndex(5),
+        StateScoped(GameplayState::Turn),
+        children![message(trigger.event().0)],
+    ));
+}
+
+fn message(reason: OverloadReason) -> impl Bundle {
+    let text = match reason {
+        OverloadReason::TooManyCommands => "Overload ! Too much is going on.",
+        OverloadReason::TooManyActivations => "Overload ! Too many activations.",
+        OverloadReason::Loop => "Overload ! The board is going around in circles.",
+    };
+    (
+        Node {
+            padding: UiRect::axes(Val::Px(20.), Val::Px(10.0)),
+            ..default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        SmartText::new(text, 30.),
+    )
+}
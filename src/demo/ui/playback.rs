@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+
+use crate::demo::{
+    GameplayState,
+    turn::PlaybackSpeed,
+    ui::smart_text::{SmartText, UpdateNamedValueEvent},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(
+        OnEnter(GameplayState::Turn),
+        (spawn_playback_ui, update_speed),
+    );
+    app.add_systems(
+        Update,
+        update_speed.run_if(in_state(GameplayState::Turn).and(resource_changed::<PlaybackSpeed>)),
+    );
+}
+
+fn spawn_playback_ui(mut commands: Commands) {
+    commands.spawn((
+        Name::new("Playback"),
+        Node {
+            position_type: PositionType::Absolute,
+            bottom: Val::Px(0.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            margin: UiRect::all(Val::Px(10.)),
+            ..default()
+        },
+        Pickable::IGNORE,
+        StateScoped(GameplayState::Turn),
+        children![(
+            Node {
+                margin: UiRect::axes(Val::Px(20.), Val::Px(5.)),
+                ..default()
+            },
+            SmartText::new(
+                "Speed {named:playback_speed}x    1 2 3 Change speed    Enter Skip to end",
+                18.
+            )
+        )],
+    ));
+}
+
+fn update_speed(mut commands: Commands, speed: Res<PlaybackSpeed>) {
+    commands.trigger(UpdateNamedValueEvent {
+        name: "playback_speed".to_string(),
+        value: speed.time_scale().to_string(),
+    });
+}
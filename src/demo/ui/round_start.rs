@@ -0,0 +1,55 @@
+use bevy::prelude::*;
+
+use crate::{
+    demo::{GameplayState, ui::smart_text::SmartText},
+    model::game::Game,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(on_round_start);
+}
+
+/// A round just started; shows its modifiers, if it has any.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct RoundStartEvent;
+
+fn on_round_start(_: Trigger<RoundStartEvent>, mut commands: Commands, game: Res<Game>) {
+    let rules = game.round_rules();
+    if rules.is_empty() {
+        return;
+    }
+    let mut lines = vec![match &rules.boss {
+        Some(boss) => format!("Round {}: boss round, {boss} !", game.round()),
+        None => format!("Round {}", game.round()),
+    }];
+    lines.extend(
+        rules
+            .modifiers
+            .iter()
+            .map(|modifier| modifier.description()),
+    );
+    commands.spawn((
+        Name::new("Round Start Banner"),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(90.0),
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        GlobalZIndex(5),
+        Pickable::IGNORE,
+        StateScoped(GameplayState::Placement),
+        children![(
+            Node {
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                padding: UiRect::axes(Val::Px(20.), Val::Px(10.0)),
+                ..default()
+            },
+            BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+            Pickable::IGNORE,
+            SmartText::new(&lines.join("\n"), 24.),
+        )],
+    ));
+}
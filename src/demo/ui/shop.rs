@@ -6,12 +6,15 @@ use bevy::{
 use crate::{
     demo::{
         GameplayState,
+        actor::tier_numeral,
         drag::{DragSource, StartDragEvent},
         ui::smart_text::{SmartText, UpdateNamedValueEvent},
     },
     model::{
         actor_type::{ActorType, ActorTypeId},
+        actor_types::MERGE_COUNT,
         game::Game,
+        history::{History, Purchase},
     },
     theme::{
         interaction::SetButtonSelectedEvent,
@@ -94,13 +97,40 @@ fn on_populate_shop_items(
     mut commands: Commands,
     game: Res<Game>,
     mut q_hideable: Query<&mut Node, With<InfoHideable>>,
+    mut q_panel: Query<&mut Node, (With<ShopItemsPanel>, Without<InfoHideable>)>,
 ) {
     let panel_entity = trigger.target();
+    if let Ok(mut node) = q_panel.get_mut(panel_entity) {
+        let columns = game
+            .shop()
+            .stock()
+            .count()
+            .div_ceil(SLOTS_PER_COLUMN)
+            .max(1);
+        node.grid_template_columns = RepeatedGridTrack::flex(columns as u16, 1.0);
+    }
     commands.entity(panel_entity).despawn_related::<Children>();
     commands.entity(panel_entity).with_children(|commands| {
-        for actor_type_id in game.shop().stock() {
+        for (index, actor_type_id) in game.shop().stock().enumerate() {
             let actor_type = game.actor_types().get(actor_type_id).unwrap();
-            commands.spawn(shop_item(actor_type, actor_type_id.clone()));
+            let merge_progress = game
+                .actor_types()
+                .upgrade(actor_type_id)
+                .map(|_| game.merge_candidates(actor_type_id, 1).len());
+            commands.spawn((
+                Name::new("Shop slot"),
+                Node {
+                    width: Percent(100.),
+                    flex_direction: FlexDirection::Row,
+                    align_items: AlignItems::Stretch,
+                    column_gap: Px(5.),
+                    ..default()
+                },
+                children![
+                    shop_item(actor_type, actor_type_id.clone(), merge_progress),
+                    lock_toggle(index, game.shop().is_locked(index)),
+                ],
+            ));
         }
     });
     for mut node in q_hideable.iter_mut() {
@@ -109,19 +139,68 @@ fn on_populate_shop_items(
 }
 
 const ITEM_ICON_SIZE: f32 = 10.0;
+/// Past this many, shop slots go on more columns.
+const SLOTS_PER_COLUMN: usize = 3;
 
 #[derive(Debug, Clone, Component)]
 struct ShopItem;
 
-fn shop_item(actor_type: &ActorType, actor_type_id: ActorTypeId) -> impl Bundle {
+/// `merge_progress` is how many tier 1 copies are on the board, if the type can be upgraded.
+fn shop_item(
+    actor_type: &ActorType,
+    actor_type_id: ActorTypeId,
+    merge_progress: Option<usize>,
+) -> impl Bundle {
     (
         ShopItem,
         actor_type_id,
         actor_type.clone(),
-        content_button(shop_item_button_content(actor_type), on_shop_item_clicked),
+        content_button(
+            shop_item_button_content(actor_type, merge_progress),
+            on_shop_item_clicked,
+        ),
     )
 }
 
+/// Toggles the lock of the shop slot at that index.
+#[derive(Debug, Clone, Copy, Component)]
+struct LockToggle(usize);
+
+fn lock_toggle(index: usize, locked: bool) -> impl Bundle {
+    let text = if locked { "Locked" } else { "Lock" };
+    (
+        LockToggle(index),
+        content_button(
+            (
+                Node::DEFAULT,
+                SmartText {
+                    text: text.to_string(),
+                    font_size: 12.,
+                },
+            ),
+            on_lock_toggle_clicked,
+        ),
+    )
+}
+
+fn on_lock_toggle_clicked(
+    trigger: Trigger<ButtonClick>,
+    mut commands: Commands,
+    child_of: Query<&ChildOf>,
+    lock_toggles: Query<&LockToggle>,
+    mut game: ResMut<Game>,
+    shop_items_panel: Single<Entity, With<ShopItemsPanel>>,
+    mut selected_actor_type: ResMut<SelectedActorType>,
+) {
+    let parent = child_of.get(trigger.target()).unwrap().parent();
+    if let Ok(LockToggle(index)) = lock_toggles.get(parent) {
+        game.toggle_shop_lock(*index);
+        selected_actor_type.0 = None;
+        commands.trigger(UpdateBuyButtonEvent);
+        commands.trigger_targets(PopulateShopItemsEvent, shop_items_panel.into_inner());
+    }
+}
+
 fn on_shop_item_clicked(
     trigger: Trigger<ButtonClick>,
     mut commands: Commands,
@@ -173,8 +252,12 @@ fn on_shop_item_clicked(
     commands.trigger(UpdateBuyButtonEvent);
 }
 
-fn shop_item_button_content(actor_type: &ActorType) -> impl Bundle {
+fn shop_item_button_content(actor_type: &ActorType, merge_progress: Option<usize>) -> impl Bundle {
     let font_size = 12.;
+    let tier_text = match merge_progress {
+        Some(count) => format!("{}: {count}/{MERGE_COUNT}", tier_numeral(2)),
+        None => String::new(),
+    };
     (
         Node {
             align_items: AlignItems::Center,
@@ -214,6 +297,15 @@ fn shop_item_button_content(actor_type: &ActorType) -> impl Bundle {
                     font_size,
                 },
             ),
+            (
+                Name::new("Actor type merge progress"),
+                Node::DEFAULT,
+                Pickable::IGNORE,
+                SmartText {
+                    text: tier_text,
+                    font_size,
+                },
+            ),
         ],
     )
 }
@@ -307,13 +399,16 @@ fn items_panel() -> impl Bundle {
             (
                 ShopItemsPanel,
                 Node {
+                    display: Display::Grid,
                     width: Vh(50.),
                     height: Percent(100.),
                     // flex_grow: 1.0,
-                    flex_direction: FlexDirection::Column,
-                    justify_content: JustifyContent::Center,
+                    grid_auto_flow: GridAutoFlow::Column,
+                    grid_template_rows: RepeatedGridTrack::auto(SLOTS_PER_COLUMN as u16),
+                    align_content: AlignContent::Center,
                     align_items: AlignItems::Center,
                     row_gap: Px(10.),
+                    column_gap: Px(10.),
                     ..default()
                 },
                 BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
@@ -442,6 +537,7 @@ fn info_numbers() -> impl Bundle {
 }
 
 #[derive(Component)]
+#[allow(dead_code)]
 pub struct RestockCostText;
 
 #[derive(Component)]
@@ -487,7 +583,11 @@ fn on_restock_button_clicked(
     mut game: ResMut<Game>,
     shop_items_panel: Single<Entity, With<ShopItemsPanel>>,
     mut selected_actor_type: ResMut<SelectedActorType>,
+    mut history: ResMut<History>,
 ) {
+    if game.can_restock() {
+        history.record(game.clone());
+    }
     game.restock();
     selected_actor_type.0 = None;
     commands.trigger(UpdateRestockButtonEvent);
@@ -543,12 +643,10 @@ fn on_buy_button_clicked(
         shop_index,
     }) = selected_actor_type.0.as_ref()
     {
-        if game.buy_item(actor_type_id) {
+        if let Some(purchase) = Purchase::buy(&mut game, actor_type_id, *shop_index) {
             commands.trigger(StartDragEvent {
-                actor_type_id: actor_type_id.clone(),
-                source: DragSource::Shop {
-                    shop_index: *shop_index,
-                },
+                actor_type_id: purchase.actor_type_id().clone(),
+                source: DragSource::Shop(Box::new(purchase)),
             });
         }
     }
@@ -0,0 +1,95 @@
+use std::fmt::Write;
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::{GameplayState, turn::StepDebug},
+    model::game::Game,
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(GameplayState::Turn), spawn_step_panel);
+    app.add_systems(
+        Update,
+        update_step_panel.run_if(
+            in_state(GameplayState::Turn)
+                .and(resource_changed::<StepDebug>.or(resource_changed::<Game>)),
+        ),
+    );
+}
+
+#[derive(Component)]
+struct StepPanel;
+
+#[derive(Component)]
+struct StepPanelText;
+
+fn spawn_step_panel(mut commands: Commands) {
+    commands.spawn((
+        StepPanel,
+        Name::new("Step Panel"),
+        Node {
+            position_type: PositionType::Absolute,
+            right: Val::Px(10.0),
+            top: Val::Px(90.0),
+            min_width: Val::Px(250.0),
+            padding: UiRect::all(Val::Px(10.)),
+            display: Display::None,
+            ..default()
+        },
+        BackgroundColor(Color::linear_rgba(0.0, 0.0, 0.0, 0.8)),
+        GlobalZIndex(5),
+        Pickable::IGNORE,
+        StateScoped(GameplayState::Turn),
+        children![(
+            StepPanelText,
+            Text::default(),
+            TextFont::from_font_size(16.),
+        )],
+    ));
+}
+
+fn update_step_panel(
+    step_debug: Res<StepDebug>,
+    game: Res<Game>,
+    mut q_panel: Query<&mut Node, With<StepPanel>>,
+    mut q_text: Query<&mut Text, With<StepPanelText>>,
+) {
+    for mut node in &mut q_panel {
+        node.display = if step_debug.enabled {
+            Display::Flex
+        } else {
+            Display::None
+        };
+    }
+    if !step_debug.enabled {
+        return;
+    }
+    let mut text = String::from("Step mode: N next command, F8 leave\n\n");
+    match &step_debug.current {
+        Some(cmd) => writeln!(text, "Command: {cmd:?}").unwrap(),
+        None => writeln!(text, "Command: -").unwrap(),
+    }
+    let stack: Vec<String> = step_debug
+        .activation_stack
+        .iter()
+        .map(|actor_id| (**actor_id).to_string())
+        .collect();
+    writeln!(text, "Activation stack: [{}]", stack.join(", ")).unwrap();
+    writeln!(text, "\nActivations left:").unwrap();
+    let mut actor_ids: Vec<_> = game.board().actor_ids().copied().collect();
+    actor_ids.sort_by_key(|actor_id| **actor_id);
+    for actor_id in actor_ids {
+        if let Some(view) = game.actor_view(&actor_id) {
+            writeln!(
+                text,
+                "  {} {}: {}",
+                *actor_id, view.actor_type.name, view.actor.activations_left
+            )
+            .unwrap();
+        }
+    }
+    for mut panel_text in &mut q_text {
+        panel_text.0.clone_from(&text);
+    }
+}
@@ -50,14 +50,27 @@ fn on_update_top_bar(_: Trigger<UpdateTopBarEvent>, mut commands: Commands, game
         name: "round".to_string(),
         value: game.round().to_string(),
     });
+
+    let income = game.last_income();
+    for (name, value) in [
+        ("income_prizes", income.prizes),
+        ("income_interest", income.interest),
+        ("income_stipend", income.stipend),
+        ("income_streak", income.streak),
+    ] {
+        commands.trigger(UpdateNamedValueEvent {
+            name: name.to_string(),
+            value: value.to_string(),
+        });
+    }
 }
 
 fn update_top_bar(mut commands: Commands) {
     commands.trigger(UpdateTopBarEvent);
 }
 
-fn enable_shop_button(mut commands: Commands) {
-    set_enabled::<ShopButton>(&mut commands, true);
+fn enable_shop_button(mut commands: Commands, game: Res<Game>) {
+    set_enabled::<ShopButton>(&mut commands, !game.shop().is_closed());
 }
 
 fn disable_shop_button(mut commands: Commands) {
@@ -82,14 +95,21 @@ pub(super) fn top_bar_ui() -> impl Bundle {
             ..default()
         },
         Pickable::IGNORE,
-        children![gold_ui(), shop_button_part_ui(), turns_left_ui()],
+        children![
+            gold_ui(),
+            income_ui(),
+            shop_button_part_ui(),
+            turns_left_ui()
+        ],
     )
 }
 
 #[derive(Component)]
+#[allow(dead_code)]
 struct CurrentGoldText;
 
 #[derive(Component)]
+#[allow(dead_code)]
 struct RequiredGoldText;
 
 const TEXT_SIZE: f32 = 25.;
@@ -110,6 +130,26 @@ pub(super) fn gold_ui() -> impl Bundle {
     )
 }
 
+/// Where the gold of the last turn came from.
+fn income_ui() -> impl Bundle {
+    (
+        Name::new("Income Part"),
+        Node {
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::FlexStart,
+            flex_direction: FlexDirection::Row,
+            margin: UiRect::left(Val::Px(20.)),
+            ..default()
+        },
+        SmartText {
+            font_size: INCOME_TEXT_SIZE,
+            text: "Last turn: {named:income_prizes} prizes + {named:income_interest} interest + {named:income_stipend} stipend + {named:income_streak} streak".to_string(),
+        },
+    )
+}
+
+const INCOME_TEXT_SIZE: f32 = 16.;
+
 #[derive(Component)]
 struct ShopButtonPart;
 #[derive(Component)]
@@ -142,9 +182,11 @@ fn on_shop_button_clicked(
 }
 
 #[derive(Component)]
+#[allow(dead_code)]
 struct TurnsLeftText;
 
 #[derive(Component)]
+#[allow(dead_code)]
 struct RoundText;
 
 pub(super) fn turns_left_ui() -> impl Bundle {
@@ -0,0 +1,85 @@
+use bevy::prelude::*;
+
+use crate::{
+    demo::{
+        GameplayState,
+        level::ResetBoardEvent,
+        ui::{
+            game_over::{summary_window, update_run_stats},
+            round_start::RoundStartEvent,
+            top_bar::UpdateTopBarEvent,
+        },
+    },
+    model::{game::Game, rounds::RoundSchedule},
+    screens::Screen,
+    theme::widget::{self, center_ui_root},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_observer(on_victory);
+}
+
+/// The final round was won.
+#[derive(Debug, Clone, Copy, Event)]
+pub struct VictoryEvent;
+
+fn on_victory(
+    _: Trigger<VictoryEvent>,
+    mut commands: Commands,
+    mut next_state: ResMut<NextState<GameplayState>>,
+    game: Res<Game>,
+) {
+    next_state.set(GameplayState::Victory);
+
+    commands.spawn((
+        center_ui_root("Victory Ui"),
+        GlobalZIndex(6),
+        StateScoped(GameplayState::Victory),
+        children![summary_window(
+            "Victory !",
+            "The machine runs like clockwork.",
+            "You made {named:total_gold}{icon:coin} and beat round {named:round}{icon:round}.",
+            buttons(),
+        )],
+    ));
+    update_run_stats(&mut commands, &game);
+}
+
+fn buttons() -> impl Bundle {
+    (
+        Node {
+            width: Val::Percent(100.),
+            justify_content: JustifyContent::Center,
+            column_gap: Val::Px(20.),
+            ..default()
+        },
+        children![
+            widget::button_small("Endless", on_endless_button_clicked),
+            widget::button_small("Done", on_done_button_clicked),
+        ],
+    )
+}
+
+fn on_endless_button_clicked(
+    _: Trigger<widget::ButtonClick>,
+    mut commands: Commands,
+    mut game: ResMut<Game>,
+    schedule: Res<RoundSchedule>,
+    mut next_gameplay_state: ResMut<NextState<GameplayState>>,
+) {
+    game.start_endless();
+    game.next_round(&schedule);
+    next_gameplay_state.set(GameplayState::Placement);
+    commands.trigger(ResetBoardEvent);
+    commands.trigger(UpdateTopBarEvent);
+    commands.trigger(RoundStartEvent);
+}
+
+fn on_done_button_clicked(
+    _: Trigger<widget::ButtonClick>,
+    mut next_screen_state: ResMut<NextState<Screen>>,
+    mut next_gameplay_state: ResMut<NextState<GameplayState>>,
+) {
+    next_gameplay_state.set(GameplayState::WorkaroundBugs);
+    next_screen_state.set(Screen::Title);
+}
@@ -0,0 +1,115 @@
+// Support configuring Bevy lints within code.
+#![cfg_attr(bevy_lint, feature(register_tool), register_tool(bevy))]
+
+mod asset_tracking;
+mod audio;
+mod camera;
+pub mod data;
+mod demo;
+#[cfg(feature = "dev")]
+mod dev_tools;
+mod menus;
+pub mod model;
+mod persistence;
+mod screens;
+pub mod sim;
+mod theme;
+
+use bevy::{asset::AssetMetaCheck, prelude::*};
+use bevy_tween::DefaultTweenPlugins;
+// use bevy_tweening::TweeningPlugin;
+use camera::MainCamera;
+
+pub struct AppPlugin;
+
+impl Plugin for AppPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(ClearColor(Color::srgb(1., 0.5, 0.5)));
+        // Add Bevy plugins.
+        app.add_plugins((DefaultPlugins
+            .set(AssetPlugin {
+                // Wasm builds will check for meta files (that don't exist) if this isn't set.
+                // This causes errors and even panics on web build on itch.
+                // See https://github.com/bevyengine/bevy_github_ci_template/issues/48.
+                meta_check: AssetMetaCheck::Never,
+                ..default()
+            })
+            .set(WindowPlugin {
+                primary_window: Window {
+                    title: "Bj6".to_string(),
+                    fit_canvas_to_parent: true,
+                    position: if cfg!(feature = "dev") && cfg!(target_os = "windows") {
+                        WindowPosition::new(ivec2(1920, 0))
+                    } else {
+                        WindowPosition::Automatic
+                    },
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .set(ImagePlugin::default_nearest()),));
+
+        // Add other plugins.
+        app.add_plugins((
+            DefaultTweenPlugins,
+            asset_tracking::plugin,
+            data::plugin,
+            audio::plugin,
+            #[cfg(feature = "dev")]
+            dev_tools::plugin,
+            screens::plugin,
+            menus::plugin,
+            persistence::plugin,
+            theme::plugin,
+            demo::plugin,
+        ));
+
+        // Order new `AppSystems` variants by adding them here:
+        app.configure_sets(
+            Update,
+            (
+                AppSystems::TickTimers,
+                AppSystems::GatherActions,
+                AppSystems::RecordInput,
+                AppSystems::Update,
+            )
+                .chain(),
+        );
+
+        // Set up the `Pause` state.
+        app.init_state::<Pause>();
+        app.configure_sets(Update, PausableSystems.run_if(in_state(Pause(false))));
+
+        // Spawn the main camera.
+        app.add_systems(Startup, spawn_camera);
+    }
+}
+
+/// High-level groupings of systems for the app in the `Update` schedule.
+/// When adding a new variant, make sure to order it in the `configure_sets`
+/// call above.
+#[derive(SystemSet, Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+enum AppSystems {
+    /// Tick timers.
+    TickTimers,
+    /// compute available actions
+    GatherActions,
+    /// Record player input.
+    RecordInput,
+    /// Do everything else (consider splitting this into further variants).
+    Update,
+}
+
+/// Whether or not the game is paused.
+#[derive(States, Copy, Clone, Eq, PartialEq, Hash, Debug, Default)]
+#[states(scoped_entities)]
+struct Pause(pub bool);
+
+/// A system set for systems that shouldn't run while the game is paused.
+#[derive(SystemSet, Copy, Clone, Eq, PartialEq, Hash, Debug)]
+struct PausableSystems;
+
+fn spawn_camera(mut commands: Commands) {
+    commands.spawn((Name::new("Camera"), MainCamera, Camera2d));
+}
@@ -0,0 +1,306 @@
+//! The codex menu, describing every actor type one page at a time.
+
+use bevy::{
+    ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
+};
+
+use crate::{
+    menus::Menu,
+    model::{
+        actor_type::{ActorType, ActorTypeId},
+        actor_types::{ActorTypes, ActorTypesHandle, START_ACTOR_TYPE},
+        program::{DiagramMark, ProgramDiagram},
+    },
+    persistence,
+    theme::prelude::*,
+};
+
+const SPRITE_SIZE: f32 = 128.0;
+const CELL_SIZE: f32 = 24.0;
+const TEXT_WIDTH: f32 = 520.0;
+
+pub(super) fn plugin(app: &mut App) {
+    app.init_resource::<CodexPage>();
+    app.init_resource::<CodexSettings>();
+    app.add_systems(
+        OnEnter(Menu::Codex),
+        (load_actor_types, spawn_codex_menu).chain(),
+    );
+    app.add_systems(
+        Update,
+        (
+            spawn_codex_menu.run_if(
+                in_state(Menu::Codex).and(
+                    resource_changed::<CodexPage>
+                        .or(resource_changed::<CodexSettings>)
+                        .or(resource_exists_and_changed::<ActorTypes>),
+                ),
+            ),
+            go_back.run_if(in_state(Menu::Codex).and(input_just_pressed(KeyCode::Escape))),
+        ),
+    );
+}
+
+/// Index of the shown actor type, in cost order.
+#[derive(Resource, Debug, Default)]
+struct CodexPage(usize);
+
+#[derive(Resource, Debug, Default)]
+struct CodexSettings {
+    /// Hides the actor types that were never bought in a finished run.
+    lock_unbought: bool,
+}
+
+#[derive(Component)]
+struct CodexRoot;
+
+/// The codex can be opened from the title screen, before the loading screen
+/// had a chance to load the actor types.
+fn load_actor_types(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    handle: Option<Res<ActorTypesHandle>>,
+) {
+    if handle.is_none() {
+        commands.insert_resource(ActorTypesHandle(asset_server.load("all.actor_types.toml")));
+    }
+}
+
+fn sorted_actor_types(actor_types: &ActorTypes) -> Vec<(&ActorTypeId, &ActorType)> {
+    let mut sorted: Vec<_> = actor_types.iter().collect();
+    sorted.sort_by_key(|(actor_type_id, actor_type)| (actor_type.cost, *actor_type_id));
+    sorted
+}
+
+fn spawn_codex_menu(
+    mut commands: Commands,
+    roots: Query<Entity, With<CodexRoot>>,
+    actor_types: Option<Res<ActorTypes>>,
+    page: Res<CodexPage>,
+    settings: Res<CodexSettings>,
+) {
+    for root in &roots {
+        commands.entity(root).despawn();
+    }
+    let root = commands
+        .spawn((
+            widget::center_ui_root("Codex Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::Codex),
+            CodexRoot,
+        ))
+        .id();
+    let Some(actor_types) = actor_types else {
+        commands.entity(root).with_children(|parent| {
+            parent.spawn(widget::header("Codex"));
+            parent.spawn(widget::label("Loading..."));
+            parent.spawn(widget::button("Back", go_back_on_click));
+        });
+        return;
+    };
+
+    let sorted = sorted_actor_types(&actor_types);
+    let index = page.0 % sorted.len().max(1);
+    let bought = persistence::read_stats().bought_by_type;
+    let lock_text = if settings.lock_unbought {
+        "Show all"
+    } else {
+        "Hide unbought"
+    };
+    commands.entity(root).with_children(|parent| {
+        parent.spawn(widget::header("Codex"));
+        if let Some((actor_type_id, actor_type)) = sorted.get(index) {
+            let locked = settings.lock_unbought
+                && actor_type_id.as_str() != START_ACTOR_TYPE
+                && !bought.contains_key(*actor_type_id);
+            parent.spawn(entry(actor_type, locked));
+        }
+        parent.spawn((
+            Name::new("Pager"),
+            Node {
+                align_items: AlignItems::Center,
+                column_gap: Px(20.0),
+                ..default()
+            },
+            children![
+                widget::button_small("<", previous_page),
+                widget::label(format!("{} / {}", index + 1, sorted.len())),
+                widget::button_small(">", next_page),
+                widget::button_small(lock_text, toggle_lock),
+            ],
+        ));
+        parent.spawn(widget::button("Back", go_back_on_click));
+    });
+}
+
+fn entry(actor_type: &ActorType, locked: bool) -> impl Bundle {
+    let sprite = actor_type.sprite_handle.clone().unwrap_or_default();
+    let (lines, diagram) = if locked {
+        (
+            vec![
+                "???".to_string(),
+                "Buy it once in a run to learn more.".to_string(),
+            ],
+            None,
+        )
+    } else {
+        let yes_no = |flag| if flag { "yes" } else { "no" };
+        (
+            vec![
+                actor_type.name.clone(),
+                format!(
+                    "Cost: {}   Prize: {}   Activations: {}",
+                    actor_type.cost, actor_type.prize, actor_type.max_activations
+                ),
+                format!(
+                    "Pushable: {}   Dragable: {}   Rotatable: {}",
+                    yes_no(actor_type.pushable),
+                    yes_no(actor_type.dragable),
+                    yes_no(actor_type.rotatable)
+                ),
+                actor_type.description.clone(),
+            ],
+            Some(ProgramDiagram::new(&actor_type.program)),
+        )
+    };
+    (
+        Name::new("Codex Entry"),
+        Node {
+            column_gap: Px(30.0),
+            align_items: AlignItems::Center,
+            ..default()
+        },
+        Children::spawn((
+            Spawn((
+                Name::new("Actor type image"),
+                ImageNode {
+                    image: sprite.clone(),
+                    color: if locked { Color::BLACK } else { Color::WHITE },
+                    ..default()
+                },
+                Node {
+                    width: Px(SPRITE_SIZE),
+                    height: Px(SPRITE_SIZE),
+                    ..default()
+                },
+            )),
+            Spawn((
+                Name::new("Details"),
+                Node {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Px(8.0),
+                    width: Px(TEXT_WIDTH),
+                    ..default()
+                },
+                Children::spawn(SpawnIter(
+                    lines
+                        .into_iter()
+                        .enumerate()
+                        .map(|(i, line)| text(line, if i == 0 { 32.0 } else { 20.0 })),
+                )),
+            )),
+            SpawnIter(
+                diagram
+                    .map(|diagram| diagram_grid(diagram, sprite))
+                    .into_iter(),
+            ),
+        )),
+    )
+}
+
+fn text(text: String, font_size: f32) -> impl Bundle {
+    (
+        Name::new("Label"),
+        Text(text),
+        TextFont::from_font_size(font_size),
+        TextColor(ui_palette::LABEL_TEXT),
+    )
+}
+
+fn mark_color(mark: DiagramMark) -> Color {
+    match mark {
+        DiagramMark::Path => Color::srgb(0.275, 0.400, 0.750),
+        DiagramMark::Hit => Color::srgb(0.85, 0.25, 0.25),
+        DiagramMark::Push => Color::srgb(0.867, 0.827, 0.412),
+        DiagramMark::Spawn => Color::srgb(0.30, 0.70, 0.35),
+        DiagramMark::Destroy => Color::srgb(0.55, 0.30, 0.65),
+    }
+}
+
+/// The board around the actor, looking right, with the tiles its program
+/// acts on colored after the last thing done there.
+fn diagram_grid(diagram: ProgramDiagram, sprite: Handle<Image>) -> impl Bundle {
+    let (min, max) = diagram.bounds();
+    let size = max - min + IVec2::ONE;
+    // Rows go down the screen while board coordinates go up.
+    let cells: Vec<_> = (0..size.y)
+        .flat_map(|row| (0..size.x).map(move |column| ivec2(min.x + column, max.y - row)))
+        .map(|coord| {
+            let color = diagram
+                .marks_at(coord)
+                .last()
+                .map_or(Color::srgba(0.0, 0.0, 0.0, 0.5), mark_color);
+            let image = (coord == IVec2::ZERO).then(|| ImageNode::new(sprite.clone()));
+            (
+                Name::new("Diagram Cell"),
+                Node {
+                    width: Px(CELL_SIZE),
+                    height: Px(CELL_SIZE),
+                    ..default()
+                },
+                BackgroundColor(color),
+                Children::spawn(SpawnIter(image.into_iter().map(|image| {
+                    (
+                        image,
+                        Node {
+                            width: Percent(100.0),
+                            height: Percent(100.0),
+                            ..default()
+                        },
+                    )
+                }))),
+            )
+        })
+        .collect();
+    (
+        Name::new("Program Diagram"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(2.0),
+            column_gap: Px(2.0),
+            grid_template_columns: RepeatedGridTrack::px(size.x as u16, CELL_SIZE),
+            ..default()
+        },
+        Children::spawn(SpawnIter(cells.into_iter())),
+    )
+}
+
+fn previous_page(
+    _: Trigger<Pointer<Click>>,
+    actor_types: Option<Res<ActorTypes>>,
+    mut page: ResMut<CodexPage>,
+) {
+    let count = actor_types.map_or(1, |actor_types| actor_types.iter().count().max(1));
+    page.0 = (page.0 % count + count - 1) % count;
+}
+
+fn next_page(
+    _: Trigger<Pointer<Click>>,
+    actor_types: Option<Res<ActorTypes>>,
+    mut page: ResMut<CodexPage>,
+) {
+    let count = actor_types.map_or(1, |actor_types| actor_types.iter().count().max(1));
+    page.0 = (page.0 % count + 1) % count;
+}
+
+fn toggle_lock(_: Trigger<Pointer<Click>>, mut settings: ResMut<CodexSettings>) {
+    settings.lock_unbought = !settings.lock_unbought;
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
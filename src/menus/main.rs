@@ -2,33 +2,63 @@
 
 use bevy::prelude::*;
 
-use crate::{asset_tracking::ResourceHandles, menus::Menu, screens::Screen, theme::widget};
+use crate::{
+    asset_tracking::ResourceHandles,
+    demo::replay::Replay,
+    menus::Menu,
+    persistence::{self, PendingSave},
+    screens::Screen,
+    theme::widget,
+};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Main), spawn_main_menu);
 }
 
 fn spawn_main_menu(mut commands: Commands) {
-    commands.spawn((
-        widget::center_ui_root("Main Menu"),
-        GlobalZIndex(2),
-        StateScoped(Menu::Main),
-        #[cfg(not(target_family = "wasm"))]
-        children![
-            title(),
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-            widget::button("Exit", exit_app),
-        ],
-        #[cfg(target_family = "wasm")]
-        children![
-            title(),
-            widget::button("Play", enter_loading_or_gameplay_screen),
-            widget::button("Settings", open_settings_menu),
-            widget::button("Credits", open_credits_menu),
-        ],
-    ));
+    let root = commands
+        .spawn((
+            widget::center_ui_root("Main Menu"),
+            GlobalZIndex(2),
+            StateScoped(Menu::Main),
+            #[cfg(not(target_family = "wasm"))]
+            children![
+                title(),
+                widget::button("Play", enter_loading_or_gameplay_screen),
+                widget::button("Settings", open_settings_menu),
+                widget::button("Stats", open_stats_menu),
+                widget::button("Codex", open_codex_menu),
+                widget::button("Credits", open_credits_menu),
+                widget::button("Exit", exit_app),
+            ],
+            #[cfg(target_family = "wasm")]
+            children![
+                title(),
+                widget::button("Play", enter_loading_or_gameplay_screen),
+                widget::button("Settings", open_settings_menu),
+                widget::button("Stats", open_stats_menu),
+                widget::button("Codex", open_codex_menu),
+                widget::button("Credits", open_credits_menu),
+            ],
+        ))
+        .id();
+    if persistence::has_save() {
+        let continue_button = commands
+            .spawn(widget::button("Continue", continue_saved_run))
+            .id();
+        // Right under the title, above "Play".
+        commands.entity(root).insert_children(1, &[continue_button]);
+    }
+    if persistence::has_replay() {
+        let replay_button = commands
+            .spawn(widget::button("Watch replay", watch_replay))
+            .id();
+        // Right above "Settings".
+        let index = if persistence::has_save() { 3 } else { 2 };
+        commands
+            .entity(root)
+            .insert_children(index, &[replay_button]);
+    }
 }
 
 fn title() -> impl Bundle {
@@ -58,10 +88,47 @@ fn enter_loading_or_gameplay_screen(
     }
 }
 
+fn continue_saved_run(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    resource_handles: Res<ResourceHandles>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(save) = persistence::read_save() else {
+        return;
+    };
+    commands.insert_resource(PendingSave(save));
+    enter_loading_or_gameplay_screen(trigger, resource_handles, next_screen);
+}
+
+fn watch_replay(
+    trigger: Trigger<Pointer<Click>>,
+    mut commands: Commands,
+    resource_handles: Res<ResourceHandles>,
+    next_screen: ResMut<NextState<Screen>>,
+) {
+    let Some(replay) = persistence::read_replay() else {
+        return;
+    };
+    commands.insert_resource(Replay {
+        turn: replay.turn,
+        viewer: true,
+    });
+    enter_loading_or_gameplay_screen(trigger, resource_handles, next_screen);
+}
+
 fn open_settings_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Settings);
 }
 
+fn open_stats_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Stats);
+}
+
+fn open_codex_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Codex);
+}
+
 fn open_credits_menu(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
     next_menu.set(Menu::Credits);
 }
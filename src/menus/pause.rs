@@ -2,7 +2,7 @@
 
 use bevy::{input::common_conditions::input_just_pressed, prelude::*};
 
-use crate::{menus::Menu, screens::Screen, theme::widget};
+use crate::{menus::Menu, model::game::Game, screens::Screen, theme::widget};
 
 pub(super) fn plugin(app: &mut App) {
     app.add_systems(OnEnter(Menu::Pause), spawn_pause_menu);
@@ -12,13 +12,15 @@ pub(super) fn plugin(app: &mut App) {
     );
 }
 
-fn spawn_pause_menu(mut commands: Commands) {
+fn spawn_pause_menu(mut commands: Commands, game: Option<Res<Game>>) {
+    let seed = game.map_or_else(String::new, |game| format!("Seed: {}", game.seed()));
     commands.spawn((
         widget::center_ui_root("Pause Menu"),
         GlobalZIndex(10),
         StateScoped(Menu::Pause),
         children![
             widget::header("Game paused"),
+            widget::label(seed),
             widget::button("Continue", close_menu),
             widget::button("Settings", open_settings_menu),
             widget::button("Quit to title", quit_to_title),
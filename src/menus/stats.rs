@@ -0,0 +1,89 @@
+//! The stats menu, with the records of past runs.
+
+use bevy::{
+    ecs::spawn::SpawnIter, input::common_conditions::input_just_pressed, prelude::*, ui::Val::*,
+};
+
+use crate::{menus::Menu, model::actor_types::ActorTypes, persistence, theme::prelude::*};
+
+/// How many actor types the "most bought" list shows.
+const MOST_USED_COUNT: usize = 3;
+
+pub(super) fn plugin(app: &mut App) {
+    app.add_systems(OnEnter(Menu::Stats), spawn_stats_menu);
+    app.add_systems(
+        Update,
+        go_back.run_if(in_state(Menu::Stats).and(input_just_pressed(KeyCode::Escape))),
+    );
+}
+
+/// Actor types are named if their data is loaded already.
+fn spawn_stats_menu(mut commands: Commands, actor_types: Option<Res<ActorTypes>>) {
+    let stats = persistence::read_stats();
+    let mut rows = vec![
+        ["Runs".to_string(), stats.run_count.to_string()],
+        [
+            "Best total gold".to_string(),
+            stats.best_total_gold.to_string(),
+        ],
+        ["Highest round".to_string(), stats.highest_round.to_string()],
+        ["Longest chain".to_string(), stats.longest_chain.to_string()],
+    ];
+    let most_used = stats.most_used(MOST_USED_COUNT);
+    for (index, (actor_type_id, bought)) in most_used.iter().enumerate() {
+        let label = if index == 0 { "Most bought" } else { "" };
+        let name = actor_types
+            .as_ref()
+            .and_then(|actor_types| actor_types.get(actor_type_id))
+            .map_or(actor_type_id.as_str(), |actor_type| {
+                actor_type.name.as_str()
+            });
+        rows.push([label.to_string(), format!("{name} ({bought})")]);
+    }
+    commands.spawn((
+        widget::center_ui_root("Stats Menu"),
+        GlobalZIndex(2),
+        StateScoped(Menu::Stats),
+        children![
+            widget::header("Stats"),
+            grid(rows),
+            widget::button("Back", go_back_on_click),
+        ],
+    ));
+}
+
+fn grid(content: Vec<[String; 2]>) -> impl Bundle {
+    (
+        Name::new("Grid"),
+        Node {
+            display: Display::Grid,
+            row_gap: Px(10.0),
+            column_gap: Px(30.0),
+            grid_template_columns: RepeatedGridTrack::px(2, 400.0),
+            ..default()
+        },
+        Children::spawn(SpawnIter(content.into_iter().flatten().enumerate().map(
+            |(i, text)| {
+                (
+                    widget::label(text),
+                    Node {
+                        justify_self: if i % 2 == 0 {
+                            JustifySelf::End
+                        } else {
+                            JustifySelf::Start
+                        },
+                        ..default()
+                    },
+                )
+            },
+        ))),
+    )
+}
+
+fn go_back_on_click(_: Trigger<Pointer<Click>>, mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
+
+fn go_back(mut next_menu: ResMut<NextState<Menu>>) {
+    next_menu.set(Menu::Main);
+}
@@ -4,13 +4,20 @@ use crate::model::{
 };
 use bevy::prelude::*;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Actor {
     pub actor_type_id: ActorTypeId,
     pub looks_to: Dir,
     pub activations_left: u8,
     pub coord: IVec2,
     pub activated: bool,
+    /// Goes up when copies merge, see [`MERGE_COUNT`](crate::model::actor_types::MERGE_COUNT).
+    #[serde(default = "default_tier")]
+    pub tier: u8,
+}
+
+fn default_tier() -> u8 {
+    1
 }
 
 impl Actor {
@@ -25,6 +32,7 @@ impl Actor {
             activations_left: actor_type.max_activations,
             coord,
             activated: false,
+            tier: 1,
         }
     }
 
@@ -33,7 +41,19 @@ impl Actor {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deref, Component, Default)]
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    Deref,
+    Component,
+    Default,
+    serde::Serialize,
+    serde::Deserialize,
+)]
 pub struct ActorId(usize);
 
 impl ActorId {
@@ -1,7 +1,19 @@
 use bevy::prelude::*;
 
 use crate::model::{direction::Dir, program::Program};
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Deref, serde::Deserialize, Component)]
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Deref,
+    serde::Serialize,
+    serde::Deserialize,
+    Component,
+)]
 #[serde(transparent)]
 pub struct ActorTypeId(String);
 
@@ -27,11 +39,26 @@ pub struct ActorType {
     pub prize: u8,
     pub sprite_name: String,
     pub cost: u32,
+    #[serde(default)]
+    pub rarity: Rarity,
+    /// The shop only offers it from that round on.
+    #[serde(default)]
+    pub min_round: Option<u32>,
     #[serde(skip_deserializing)]
     pub sprite_handle: Option<Handle<Image>>,
     pub description: String,
 }
 
+/// How often the shop offers an actor type, see
+/// [`RarityWeights`](crate::model::shop::RarityWeights).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum Rarity {
+    #[default]
+    Common,
+    Rare,
+    Epic,
+}
+
 fn default_as_true() -> bool {
     true
 }
@@ -39,3 +66,26 @@ fn default_as_true() -> bool {
 fn default_as_right() -> Dir {
     Dir::Right
 }
+
+/// The stats of a tier 2 actor, made by merging copies of its type.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Upgrade {
+    pub max_activations: u8,
+    pub prize: u8,
+    /// Runs after the base program.
+    #[serde(default)]
+    pub extra_program: Program,
+}
+
+impl ActorType {
+    pub fn upgraded(&self, upgrade: &Upgrade) -> Self {
+        let mut result = self.clone();
+        result.max_activations = upgrade.max_activations;
+        result.prize = upgrade.prize;
+        result
+            .program
+            .0
+            .extend(upgrade.extra_program.0.iter().cloned());
+        result
+    }
+}
@@ -1,25 +1,269 @@
 use bevy::{platform::collections::HashMap, prelude::*};
+use thiserror::Error;
 
-use crate::model::actor_type::{ActorType, ActorTypeId};
+use crate::model::{
+    actor_type::{ActorType, ActorTypeId, Upgrade},
+    program::{Action, Program},
+};
+
+pub const START_ACTOR_TYPE: &str = "start";
+
+/// How many actors of the same type and tier merge into the next tier.
+pub const MERGE_COUNT: usize = 3;
+pub const MAX_TIER: u8 = 2;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ActorTypeError {
+    #[error("there is no `{START_ACTOR_TYPE}` actor type")]
+    MissingStart,
+    #[error("`{}`: sprite `{}` does not exist", .0.as_str(), .1)]
+    MissingSprite(ActorTypeId, String),
+    #[error("`{}`: the start actor must cost 0, so it never shows up in the shop", .0.as_str())]
+    StartForSale(ActorTypeId),
+    #[error("`{}`: cost must be more than 0, or it never shows up in the shop", .0.as_str())]
+    NotForSale(ActorTypeId),
+    #[error("`{}`: prize ({}) must not be more than the cost ({})", .0.as_str(), .1, .2)]
+    PrizeAboveCost(ActorTypeId, u8, u32),
+    #[error("`{}`: spawns unknown actor type `{}`", .0.as_str(), .1.as_str())]
+    UnknownSpawn(ActorTypeId, ActorTypeId),
+    #[error("`{}`: there can only be one start actor, it can't be spawned", .0.as_str())]
+    SpawnsStart(ActorTypeId),
+    #[error("`{}`: max_activations must be more than 0", .0.as_str())]
+    NoActivations(ActorTypeId),
+    /// Actions are counted depth first, blocks included, from the start of the
+    /// program or of the upgrade's extra program.
+    #[error("`{}`: action {} hits no offsets", .0.as_str(), .1)]
+    EmptyHit(ActorTypeId, usize),
+    #[error("`{}`: upgrade for an unknown actor type", .0.as_str())]
+    UnknownUpgrade(ActorTypeId),
+    #[error("`{}`: the start actor can't be upgraded", .0.as_str())]
+    UpgradesStart(ActorTypeId),
+}
 
 #[derive(Debug, Clone, serde::Deserialize, Resource, Asset, TypePath, Default)]
-pub struct ActorTypes(pub HashMap<ActorTypeId, ActorType>);
+pub struct ActorTypes {
+    /// Tier 2 stats, keyed by actor type, from the `[upgrades.*]` tables.
+    #[serde(default)]
+    pub upgrades: HashMap<ActorTypeId, Upgrade>,
+    #[serde(flatten)]
+    pub types: HashMap<ActorTypeId, ActorType>,
+}
 
 impl ActorTypes {
     pub fn get(&self, actor_type_id: &ActorTypeId) -> Option<&ActorType> {
-        self.0.get(actor_type_id)
+        self.types.get(actor_type_id)
     }
 
     #[allow(dead_code)]
     pub fn new() -> Self {
-        Self(HashMap::new())
+        Self::default()
     }
 
     pub fn iter(&self) -> impl Iterator<Item = (&ActorTypeId, &ActorType)> {
-        self.0.iter()
+        self.types.iter()
+    }
+
+    pub fn upgrade(&self, actor_type_id: &ActorTypeId) -> Option<&Upgrade> {
+        self.upgrades.get(actor_type_id)
+    }
+
+    /// The actor type with the stats of that tier applied.
+    pub fn get_tier(&self, actor_type_id: &ActorTypeId, tier: u8) -> Option<ActorType> {
+        let actor_type = self.get(actor_type_id)?;
+        match self.upgrade(actor_type_id) {
+            Some(upgrade) if tier >= 2 => Some(actor_type.upgraded(upgrade)),
+            _ => Some(actor_type.clone()),
+        }
+    }
+
+    /// Collects every problem with the data, sorted by actor type.
+    /// `sprite_exists` is asked about each `sprite_name`.
+    pub fn validate(&self, sprite_exists: impl Fn(&str) -> bool) -> Vec<ActorTypeError> {
+        let mut errors = Vec::new();
+        if self
+            .get(&ActorTypeId::new(START_ACTOR_TYPE.to_string()))
+            .is_none()
+        {
+            errors.push(ActorTypeError::MissingStart);
+        }
+
+        let mut actor_types: Vec<_> = self.iter().collect();
+        actor_types.sort_by_key(|(actor_type_id, _)| *actor_type_id);
+        for (actor_type_id, actor_type) in actor_types {
+            let id = || actor_type_id.clone();
+            if !sprite_exists(&actor_type.sprite_name) {
+                errors.push(ActorTypeError::MissingSprite(
+                    id(),
+                    actor_type.sprite_name.clone(),
+                ));
+            }
+            if actor_type_id.as_str() == START_ACTOR_TYPE {
+                if actor_type.cost != 0 {
+                    errors.push(ActorTypeError::StartForSale(id()));
+                }
+            } else {
+                if actor_type.cost == 0 {
+                    errors.push(ActorTypeError::NotForSale(id()));
+                }
+                if actor_type.prize as u32 > actor_type.cost {
+                    errors.push(ActorTypeError::PrizeAboveCost(
+                        id(),
+                        actor_type.prize,
+                        actor_type.cost,
+                    ));
+                }
+            }
+            if actor_type.max_activations == 0 {
+                errors.push(ActorTypeError::NoActivations(id()));
+            }
+            self.validate_program(actor_type_id, &actor_type.program, &mut errors);
+        }
+
+        let mut upgrades: Vec<_> = self.upgrades.iter().collect();
+        upgrades.sort_by_key(|(actor_type_id, _)| *actor_type_id);
+        for (actor_type_id, upgrade) in upgrades {
+            let id = || actor_type_id.clone();
+            let Some(actor_type) = self.get(actor_type_id) else {
+                errors.push(ActorTypeError::UnknownUpgrade(id()));
+                continue;
+            };
+            if actor_type_id.as_str() == START_ACTOR_TYPE {
+                errors.push(ActorTypeError::UpgradesStart(id()));
+            }
+            if upgrade.max_activations == 0 {
+                errors.push(ActorTypeError::NoActivations(id()));
+            }
+            if upgrade.prize as u32 > actor_type.cost * MERGE_COUNT as u32 {
+                errors.push(ActorTypeError::PrizeAboveCost(
+                    id(),
+                    upgrade.prize,
+                    actor_type.cost * MERGE_COUNT as u32,
+                ));
+            }
+            self.validate_program(actor_type_id, &upgrade.extra_program, &mut errors);
+        }
+        errors
+    }
+
+    /// Checks each action of a base program, or of an upgrade's extra program.
+    fn validate_program(
+        &self,
+        actor_type_id: &ActorTypeId,
+        program: &Program,
+        errors: &mut Vec<ActorTypeError>,
+    ) {
+        let id = || actor_type_id.clone();
+        for (index, action) in program.walk().into_iter().enumerate() {
+            match action {
+                Action::Hit(offsets) if offsets.is_empty() => {
+                    errors.push(ActorTypeError::EmptyHit(id(), index + 1));
+                }
+                Action::Spawn { actor_type, .. } if actor_type.as_str() == START_ACTOR_TYPE => {
+                    errors.push(ActorTypeError::SpawnsStart(id()));
+                }
+                Action::Spawn { actor_type, .. } if self.get(actor_type).is_none() => {
+                    errors.push(ActorTypeError::UnknownSpawn(id(), actor_type.clone()));
+                }
+                _ => {}
+            }
+        }
     }
 }
 
 #[derive(Resource)]
 #[allow(dead_code)]
 pub struct ActorTypesHandle(pub Handle<ActorTypes>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_utils;
+
+    #[test]
+    fn shipped_actor_types_are_valid() {
+        let actor_types = test_utils::actor_types();
+        let sprite_exists = |name: &str| std::path::Path::new("assets/images").join(name).exists();
+        assert_eq!(actor_types.validate(sprite_exists), vec![]);
+    }
+
+    #[test]
+    fn collects_every_error() {
+        let actor_types: ActorTypes = toml::from_str(
+            r#"
+            [greedy]
+            name = "Greedy"
+            max_activations = 0
+            prize = 5
+            cost = 1
+            sprite_name = "greedy.png"
+            program = [{ type = "Hit", arg = [] }]
+            description = ""
+            "#,
+        )
+        .unwrap();
+        let greedy = || ActorTypeId::new("greedy".to_string());
+        assert_eq!(
+            actor_types.validate(|name| name != "greedy.png"),
+            vec![
+                ActorTypeError::MissingStart,
+                ActorTypeError::MissingSprite(greedy(), "greedy.png".to_string()),
+                ActorTypeError::PrizeAboveCost(greedy(), 5, 1),
+                ActorTypeError::NoActivations(greedy()),
+                ActorTypeError::EmptyHit(greedy(), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn checks_upgrade_programs() {
+        let actor_types: ActorTypes = toml::from_str(
+            r#"
+            [start]
+            name = "Start"
+            max_activations = 1
+            prize = 1
+            cost = 0
+            sprite_name = "start.png"
+            program = "hit (1, 0)"
+            description = ""
+
+            [poker]
+            name = "Poker"
+            max_activations = 1
+            prize = 1
+            cost = 1
+            sprite_name = "poker.png"
+            program = "hit (1, 0)"
+            description = ""
+
+            [upgrades.poker]
+            max_activations = 2
+            prize = 2
+            extra_program = [{ type = "Hit", arg = [] }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            actor_types.validate(|_| true),
+            vec![ActorTypeError::EmptyHit(
+                ActorTypeId::new("poker".to_string()),
+                1
+            )]
+        );
+    }
+
+    #[test]
+    fn upgrades_apply_to_tier_two() {
+        let actor_types = test_utils::actor_types();
+        let pusher = ActorTypeId::new("pusher".to_string());
+        let base = actor_types.get_tier(&pusher, 1).unwrap();
+        let upgraded = actor_types.get_tier(&pusher, 2).unwrap();
+        let upgrade = actor_types.upgrade(&pusher).unwrap();
+        assert_eq!(upgraded.prize, upgrade.prize);
+        assert_eq!(upgraded.max_activations, upgrade.max_activations);
+        assert_eq!(
+            upgraded.program.0.len(),
+            base.program.0.len() + upgrade.extra_program.0.len()
+        );
+    }
+}
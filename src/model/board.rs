@@ -4,10 +4,10 @@ use thiserror::*;
 use crate::model::{
     actor::{Actor, ActorId},
     actor_type::ActorTypeId,
-    actor_types::ActorTypes,
+    actor_types::{ActorTypes, START_ACTOR_TYPE},
 };
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct Board {
     next_actor_id: usize,
     actor_id_to_actor: im::HashMap<ActorId, Actor>,
@@ -19,11 +19,16 @@ pub struct Board {
 pub enum BoardError {
     #[error("coord already taken")]
     CoordAlreadyTaken,
+    #[error("coord index out of sync at {0}")]
+    CoordIndexMismatch(IVec2),
+    #[error("{actors} actors but {coords} indexed coords")]
+    CoordIndexCount { actors: usize, coords: usize },
 }
 
 impl Board {
     pub fn new(actor_types: &ActorTypes) -> Self {
-        let id = ActorTypeId::new("start".to_string());
+        let id = ActorTypeId::new(START_ACTOR_TYPE.to_string());
+        // Validated actor types always have a start type.
         let start_actor_type = actor_types.get(&id).unwrap();
         let start_actor = Actor::from_actor_type(&id, start_actor_type, ivec2(0, 0));
 
@@ -74,6 +79,50 @@ impl Board {
         Ok(*self.coord_to_actor_id.get(&coord).unwrap())
     }
 
+    /// Places an actor under an id that was handed out already, like when replaying
+    /// a spawn that happened on another board.
+    pub fn insert_actor(&mut self, actor_id: ActorId, actor: Actor) -> Result<(), BoardError> {
+        if self.coord_to_actor_id.contains_key(&actor.coord) {
+            return Err(BoardError::CoordAlreadyTaken);
+        }
+        self.next_actor_id = self.next_actor_id.max(*actor_id + 1);
+        self.coord_to_actor_id.insert(actor.coord, actor_id);
+        self.actor_id_to_actor.insert(actor_id, actor);
+        Ok(())
+    }
+
+    /// Removes the actor along with its entry in the coord index.
+    pub fn remove_actor(&mut self, actor_id: &ActorId) -> Option<Actor> {
+        let actor = self.actor_id_to_actor.remove(actor_id)?;
+        match self.coord_to_actor_id.get(&actor.coord) {
+            Some(indexed_id) if indexed_id == actor_id => {
+                self.coord_to_actor_id.remove(&actor.coord);
+            }
+            // Never drop the entry of another actor.
+            indexed_id => warn!(
+                "coord index out of sync at {}: {indexed_id:?} instead of {actor_id:?}",
+                actor.coord
+            ),
+        }
+        Some(actor)
+    }
+
+    /// Checks that every actor is indexed at its coord, and nothing else is.
+    pub fn check_consistency(&self) -> Result<(), BoardError> {
+        for (actor_id, actor) in &self.actor_id_to_actor {
+            if self.coord_to_actor_id.get(&actor.coord) != Some(actor_id) {
+                return Err(BoardError::CoordIndexMismatch(actor.coord));
+            }
+        }
+        if self.coord_to_actor_id.len() != self.actor_id_to_actor.len() {
+            return Err(BoardError::CoordIndexCount {
+                actors: self.actor_id_to_actor.len(),
+                coords: self.coord_to_actor_id.len(),
+            });
+        }
+        Ok(())
+    }
+
     /// updates a clone of the existing actor, then place that clone back in the store
     pub fn update_actor(&mut self, actor_id: &ActorId, f: impl FnOnce(&mut Actor)) {
         if let Some(mut actor) = self.actor_id_to_actor.get(actor_id).cloned() {
@@ -94,6 +143,15 @@ impl Board {
         }
     }
 
+    /// Keeps only the actors for which `f` returns true.
+    pub(super) fn retain_actors(&mut self, mut f: impl FnMut(&ActorId, &Actor) -> bool) {
+        self.actor_id_to_actor
+            .retain(|actor_id, actor| f(actor_id, actor));
+        let actor_id_to_actor = &self.actor_id_to_actor;
+        self.coord_to_actor_id
+            .retain(|_coord, actor_id| actor_id_to_actor.contains_key(actor_id));
+    }
+
     pub fn swap_coords(&mut self, coord1: IVec2, coord2: IVec2) {
         let actor1_id = self.coord_to_actor_id.get(&coord1).copied();
         let actor2_id = self.coord_to_actor_id.get(&coord2).copied();
@@ -130,3 +188,36 @@ impl Board {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_utils;
+
+    #[test]
+    fn removal_keeps_the_coord_index_consistent() {
+        let actor_types = test_utils::actor_types();
+        let mut board = Board::new(&actor_types);
+        let pusher_id = ActorTypeId::new("pusher".to_string());
+        let pusher = actor_types.get(&pusher_id).unwrap();
+        let first = board
+            .add_actor(Actor::from_actor_type(&pusher_id, pusher, ivec2(1, 0)))
+            .unwrap();
+        let second = board
+            .add_actor(Actor::from_actor_type(&pusher_id, pusher, ivec2(2, 0)))
+            .unwrap();
+        board.swap_coords(ivec2(1, 0), ivec2(2, 0));
+
+        let removed = board.remove_actor(&first).unwrap();
+        assert_eq!(removed.coord, ivec2(2, 0));
+        assert!(board.remove_actor(&first).is_none());
+        assert!(board.check_consistency().is_ok());
+        assert_eq!(board.coord_to_actor_id(&ivec2(1, 0)), Some(second));
+        assert!(board.coord_to_actor_id(&ivec2(2, 0)).is_none());
+        assert!(
+            board
+                .add_actor(Actor::from_actor_type(&pusher_id, pusher, ivec2(2, 0)))
+                .is_ok()
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use bevy::prelude::*;
 
 use crate::{
@@ -5,40 +7,107 @@ use crate::{
     model::{
         actor::{Actor, ActorId, ActorView},
         actor_type::ActorTypeId,
-        actor_types::ActorTypes,
+        actor_types::{ActorTypes, MAX_TIER, MERGE_COUNT},
         board::Board,
-        shop::Shop,
+        income::{Income, IncomeRules},
+        rng::GameRng,
+        rounds::{RoundRules, RoundSchedule},
+        shop::{Shop, ShopSlot},
     },
 };
 
-#[derive(Resource, Clone, Debug, Default)]
+/// What a run has done so far, for the summary at its end.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct RunStats {
+    pub turns_played: u32,
+    pub best_turn_gold: u64,
+    pub actors_bought: u32,
+    /// Most activations in a single turn.
+    #[serde(default)]
+    pub longest_chain: u32,
+    #[serde(default)]
+    pub bought_by_type: BTreeMap<ActorTypeId, u32>,
+    /// What had been bought when the run went into the stats file, at its
+    /// victory. An endless run that goes on is only topped up later.
+    #[serde(default)]
+    pub recorded_bought: Option<BTreeMap<ActorTypeId, u32>>,
+}
+
+#[derive(Resource, Clone, Debug, Default, serde::Serialize, serde::Deserialize)]
 pub struct Game {
     gold: u64,
     gold_this_turn: u64,
     total_gold: u64,
     turns_left: u64,
+    #[serde(default = "default_turns_per_round")]
+    turns_per_round: u64,
     round: u32,
     required_gold: u64,
     gold_required_multiplier: f32,
     board: Board,
+    /// Comes from the data files, so it is not part of a save.
+    #[serde(skip)]
     actor_types: ActorTypes,
     shop: Shop,
+    rng: GameRng,
+    #[serde(default)]
+    income_rules: IncomeRules,
+    /// Turns in a row that earned enough prizes for the streak bonus.
+    #[serde(default)]
+    streak: u32,
+    #[serde(default)]
+    last_income: Income,
+    #[serde(default)]
+    round_rules: RoundRules,
+    /// Winning this round wins the run, there is none if 0.
+    #[serde(default)]
+    final_round: u32,
+    /// Replaces `gold_required_multiplier` once the run goes on past the final round.
+    #[serde(default = "default_endless_multiplier")]
+    endless_gold_required_multiplier: f32,
+    #[serde(default)]
+    endless: bool,
+    #[serde(default)]
+    stats: RunStats,
+}
+
+fn default_turns_per_round() -> u64 {
+    5
+}
+
+fn default_endless_multiplier() -> f32 {
+    3.0
 }
 
 impl Game {
+    /// Starts a run with a fresh random seed.
     pub fn new(game_config: &GameGameConfig, actor_types: ActorTypes) -> Self {
+        Self::with_seed(game_config, actor_types, rand::random())
+    }
+
+    pub fn with_seed(game_config: &GameGameConfig, actor_types: ActorTypes, seed: u64) -> Self {
         let board = Board::new(&actor_types);
         let mut result = Self {
             gold: 1,
             gold_this_turn: 0,
-            turns_left: 5,
+            turns_left: game_config.turns_per_round,
+            turns_per_round: game_config.turns_per_round,
             round: 1,
             required_gold: game_config.start_required_gold,
             gold_required_multiplier: game_config.gold_required_multiplier,
             board,
             actor_types,
             total_gold: 0,
-            shop: Shop::new(game_config.restock_multiplier),
+            shop: Shop::new(game_config.shop_rules()),
+            rng: GameRng::new(seed),
+            income_rules: game_config.income,
+            streak: 0,
+            last_income: Income::default(),
+            round_rules: RoundRules::default(),
+            final_round: game_config.final_round,
+            endless_gold_required_multiplier: game_config.endless_gold_required_multiplier,
+            endless: false,
+            stats: RunStats::default(),
         };
         result.gold = game_config.start_gold;
         result
@@ -48,6 +117,14 @@ impl Game {
         self.gold
     }
 
+    pub fn set_gold(&mut self, gold: u64) {
+        self.gold = gold;
+    }
+
+    pub fn gold_this_turn(&self) -> u64 {
+        self.gold_this_turn
+    }
+
     pub fn turns_left(&self) -> u64 {
         self.turns_left
     }
@@ -56,16 +133,36 @@ impl Game {
         self.round
     }
 
+    pub fn seed(&self) -> u64 {
+        self.rng.seed()
+    }
+
+    /// Every random decision of the run should draw from this.
+    pub fn rng(&mut self) -> &mut GameRng {
+        &mut self.rng
+    }
+
+    pub fn round_rules(&self) -> &RoundRules {
+        &self.round_rules
+    }
+
+    /// Counts from 1, once the turn has started.
+    pub fn turn_of_round(&self) -> u64 {
+        self.round_rules
+            .turns(self.turns_per_round)
+            .saturating_sub(self.turns_left)
+    }
+
     pub fn required_gold(&self) -> u64 {
         self.required_gold
     }
 
     pub fn actor_view(&self, actor_id: &ActorId) -> Option<ActorView> {
         if let Some(actor) = self.board.actor_id_to_actor(actor_id) {
-            if let Some(actor_type) = self.actor_types.get(&actor.actor_type_id) {
+            if let Some(actor_type) = self.actor_types.get_tier(&actor.actor_type_id, actor.tier) {
                 return Some(ActorView {
                     actor: actor.clone(),
-                    actor_type: actor_type.clone(),
+                    actor_type,
                     actor_id: *actor_id,
                 });
             }
@@ -74,26 +171,46 @@ impl Game {
     }
 
     pub fn restock(&mut self) {
-        self.shop.restock(&self.actor_types, &mut self.gold);
+        self.shop
+            .restock(&self.actor_types, &mut self.gold, self.round, &mut self.rng);
     }
 
     pub fn can_restock(&self) -> bool {
-        self.gold >= self.shop.restock_cost()
+        !self.shop.is_closed() && self.gold >= self.shop.restock_cost()
     }
 
-    pub fn buy_item(&mut self, actor_type_id: &ActorTypeId) -> bool {
-        if let Some(actor_type) = self.actor_types.get(actor_type_id) {
-            return self
-                .shop
-                .buy_item(actor_type_id, actor_type, &mut self.gold);
-        }
-        false
+    /// Buys the shop item at `index`. Returns the slot it came from.
+    pub fn buy_item(&mut self, actor_type_id: &ActorTypeId, index: usize) -> Option<ShopSlot> {
+        let actor_type = self.actor_types.get(actor_type_id)?;
+        let slot = self
+            .shop
+            .buy_item(index, actor_type_id, actor_type, &mut self.gold)?;
+        self.stats.actors_bought += 1;
+        *self
+            .stats
+            .bought_by_type
+            .entry(actor_type_id.clone())
+            .or_default() += 1;
+        Some(slot)
+    }
+
+    /// Locked shop slots are kept by restocks, until bought.
+    pub fn toggle_shop_lock(&mut self, index: usize) {
+        self.shop.toggle_lock(index);
     }
 
-    pub fn return_item(&mut self, actor_type_id: &ActorTypeId, index: usize) {
+    /// Undoes `buy_item`, the item no longer counts as bought.
+    pub fn return_item(&mut self, actor_type_id: &ActorTypeId, slot: ShopSlot) {
         if let Some(actor_type) = self.actor_types.get(actor_type_id) {
             self.shop
-                .return_item(actor_type_id, actor_type, index, &mut self.gold);
+                .return_item(actor_type_id, actor_type, slot, &mut self.gold);
+            self.stats.actors_bought = self.stats.actors_bought.saturating_sub(1);
+            if let Some(count) = self.stats.bought_by_type.get_mut(actor_type_id) {
+                *count -= 1;
+                if *count == 0 {
+                    self.stats.bought_by_type.remove(actor_type_id);
+                }
+            }
         }
     }
 
@@ -111,10 +228,87 @@ impl Game {
         self.board.add_actor(actor).ok()
     }
 
+    /// Same type, same tier actors that would merge with one more.
+    pub fn merge_candidates(&self, actor_type_id: &ActorTypeId, tier: u8) -> Vec<ActorId> {
+        let mut result: Vec<ActorId> = self
+            .board
+            .actor_ids()
+            .filter(|actor_id| {
+                self.board.actor_id_to_actor(actor_id).is_some_and(|actor| {
+                    actor.actor_type_id == *actor_type_id && actor.tier == tier
+                })
+            })
+            .copied()
+            .collect();
+        result.sort_by_key(|actor_id| **actor_id);
+        result
+    }
+
+    /// Merges the actor with copies of itself into the next tier, when there are enough
+    /// of them and its type has an upgrade. The actor stays in place, the copies are
+    /// removed and returned.
+    pub fn merge_copies(&mut self, actor_id: &ActorId) -> Vec<ActorId> {
+        let Some(actor) = self.board.actor_id_to_actor(actor_id) else {
+            return vec![];
+        };
+        let (actor_type_id, tier) = (actor.actor_type_id.clone(), actor.tier);
+        if tier >= MAX_TIER || self.actor_types.upgrade(&actor_type_id).is_none() {
+            return vec![];
+        }
+        let copies: Vec<ActorId> = self
+            .merge_candidates(&actor_type_id, tier)
+            .into_iter()
+            .filter(|copy_id| copy_id != actor_id)
+            .take(MERGE_COUNT - 1)
+            .collect();
+        if copies.len() < MERGE_COUNT - 1 {
+            return vec![];
+        }
+        for copy_id in &copies {
+            self.remove_actor(copy_id);
+        }
+        let Some(upgraded) = self.actor_types.get_tier(&actor_type_id, tier + 1) else {
+            return copies;
+        };
+        self.update_actor(actor_id, |actor| {
+            actor.tier = tier + 1;
+            actor.activations_left = upgraded.max_activations;
+        });
+        copies
+    }
+
+    pub fn insert_actor(&mut self, actor_id: ActorId, actor: Actor) -> bool {
+        self.board.insert_actor(actor_id, actor).is_ok()
+    }
+
+    /// Returns the removed actor, `None` if it was not on the board.
+    pub fn remove_actor(&mut self, actor_id: &ActorId) -> Option<Actor> {
+        self.board.remove_actor(actor_id)
+    }
+
     pub fn update_actor(&mut self, actor_id: &ActorId, f: impl FnOnce(&mut Actor)) {
         self.board.update_actor(actor_id, f);
     }
 
+    /// What selling that actor would give back, `None` if it can't be sold.
+    pub fn sell_price(&self, actor_id: &ActorId, refund_fraction: f32) -> Option<u64> {
+        if *actor_id == self.board.start_actor_id() {
+            return None;
+        }
+        let view = self.actor_view(actor_id)?;
+        // A merged actor is worth all the copies that went into it.
+        let copies = (MERGE_COUNT as u64).pow(view.actor.tier.saturating_sub(1) as u32);
+        Some((view.actor_type.cost as f32 * copies as f32 * refund_fraction).floor() as u64)
+    }
+
+    /// Removes the actor from the board and refunds part of its cost.
+    pub fn sell_actor(&mut self, actor_id: &ActorId, refund_fraction: f32) -> Option<u64> {
+        let price = self.sell_price(actor_id, refund_fraction)?;
+        self.remove_actor(actor_id)?;
+        self.gold += price;
+        Some(price)
+    }
+
     pub fn rotate_actor(&mut self, actor_id: &ActorId) {
         self.update_actor(actor_id, |actor| actor.rotate());
     }
@@ -127,6 +321,34 @@ impl Game {
         &self.shop
     }
 
+    /// Reattaches the actor types after loading a save. Actors and shop items whose
+    /// type no longer exists are dropped, and activations are clamped to the new maximum.
+    pub fn attach_actor_types(&mut self, actor_types: ActorTypes) {
+        self.board.retain_actors(|_actor_id, actor| {
+            let known = actor_types.get(&actor.actor_type_id).is_some();
+            if !known {
+                warn!("dropping actor of unknown type {:?}", actor.actor_type_id);
+            }
+            known
+        });
+        let actor_ids: Vec<ActorId> = self.board.actor_ids().copied().collect();
+        for actor_id in actor_ids {
+            self.board.update_actor(&actor_id, |actor| {
+                if actor_types.upgrade(&actor.actor_type_id).is_none() {
+                    actor.tier = 1;
+                }
+                let max_activations = actor_types
+                    .get_tier(&actor.actor_type_id, actor.tier)
+                    .unwrap()
+                    .max_activations;
+                actor.activations_left = actor.activations_left.min(max_activations);
+            });
+        }
+        self.shop
+            .retain_stock(|actor_type_id| actor_types.get(actor_type_id).is_some());
+        self.actor_types = actor_types;
+    }
+
     pub fn set_board(&mut self, board: Board) {
         self.board = board;
     }
@@ -142,6 +364,29 @@ impl Game {
         self.total_gold += amount;
     }
 
+    /// Pays the end of turn income, once the turn has been run.
+    pub fn end_turn(&mut self) -> Income {
+        if self.income_rules.extends_streak(self.gold_this_turn) {
+            self.streak += 1;
+        } else {
+            self.streak = 0;
+        }
+        self.stats.turns_played += 1;
+        self.stats.best_turn_gold = self.stats.best_turn_gold.max(self.gold_this_turn);
+        let income = self
+            .income_rules
+            .evaluate(self.gold, self.gold_this_turn, self.streak);
+        self.gold += income.bonus();
+        self.total_gold += income.bonus();
+        self.last_income = income;
+        income
+    }
+
+    /// What the last turn earned, by source.
+    pub fn last_income(&self) -> Income {
+        self.last_income
+    }
+
     pub fn total_gold(&self) -> u64 {
         self.total_gold
     }
@@ -154,9 +399,160 @@ impl Game {
         self.turns_left == 0 && self.gold >= self.required_gold
     }
 
-    pub fn next_round(&mut self) {
+    pub fn stats(&self) -> &RunStats {
+        &self.stats
+    }
+
+    /// The run went into the stats file.
+    pub fn mark_recorded(&mut self) {
+        self.stats.recorded_bought = Some(self.stats.bought_by_type.clone());
+    }
+
+    pub fn record_chain(&mut self, activations: u32) {
+        self.stats.longest_chain = self.stats.longest_chain.max(activations);
+    }
+
+    pub fn is_endless(&self) -> bool {
+        self.endless
+    }
+
+    /// The final round is over and its gold was made, outside of endless mode.
+    pub fn is_victory(&self) -> bool {
+        !self.endless
+            && self.final_round > 0
+            && self.round >= self.final_round
+            && self.can_go_next_round()
+    }
+
+    /// Goes on after a victory, with the required gold climbing faster.
+    pub fn start_endless(&mut self) {
+        self.endless = true;
+    }
+
+    pub fn next_round(&mut self, schedule: &RoundSchedule) {
+        let multiplier = if self.endless {
+            self.endless_gold_required_multiplier
+        } else {
+            self.gold_required_multiplier
+        };
         self.round += 1;
-        self.turns_left = 5;
-        self.required_gold = (self.required_gold as f32 * self.gold_required_multiplier) as u64;
+        self.shop.new_round();
+        self.required_gold = (self.required_gold as f32 * multiplier) as u64;
+        self.start_round(schedule);
+    }
+
+    /// Applies the modifiers the schedule has for the current round.
+    pub fn start_round(&mut self, schedule: &RoundSchedule) {
+        let rules = schedule.rules(self.round);
+        self.turns_left = rules.turns(self.turns_per_round);
+        self.shop.set_closed(rules.shop_closed());
+        self.round_rules = rules;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_utils;
+
+    #[test]
+    fn selling_refunds_part_of_the_cost() {
+        let config = test_utils::game_config();
+        let mut game = Game::with_seed(&config.game, test_utils::actor_types(), 0);
+        game.set_gold(0);
+        let pusher = ActorTypeId::new("pusher".to_string());
+        let actor_id = game.new_actor(&pusher, IVec2::new(1, 0)).unwrap();
+        let cost = game.actor_types().get(&pusher).unwrap().cost as u64;
+
+        assert_eq!(game.sell_actor(&actor_id, 0.5), Some(cost / 2));
+        assert_eq!(game.gold(), cost / 2);
+        assert!(game.board().coord_to_actor_id(&IVec2::new(1, 0)).is_none());
+
+        let start_actor_id = game.board().start_actor_id();
+        assert_eq!(game.sell_actor(&start_actor_id, 0.5), None);
+    }
+
+    #[test]
+    fn winning_the_final_round_then_going_endless() {
+        let mut config = test_utils::game_config();
+        config.game.final_round = 2;
+        let schedule = RoundSchedule::default();
+        let mut game = Game::with_seed(&config.game, test_utils::actor_types(), 0);
+        game.turns_left = 0;
+        game.set_gold(game.required_gold());
+        assert!(!game.is_victory());
+
+        game.next_round(&schedule);
+        game.turns_left = 0;
+        game.set_gold(game.required_gold());
+        assert!(game.is_victory());
+
+        let required_gold = game.required_gold();
+        game.start_endless();
+        assert!(!game.is_victory());
+        game.next_round(&schedule);
+        assert_eq!(
+            game.required_gold(),
+            (required_gold as f32 * config.game.endless_gold_required_multiplier) as u64
+        );
+    }
+
+    #[test]
+    fn three_copies_merge_into_tier_two() {
+        let config = test_utils::game_config();
+        let mut game = Game::with_seed(&config.game, test_utils::actor_types(), 0);
+        let pusher = ActorTypeId::new("pusher".to_string());
+        let first = game.new_actor(&pusher, IVec2::new(1, 0)).unwrap();
+        let second = game.new_actor(&pusher, IVec2::new(2, 0)).unwrap();
+        assert!(game.merge_copies(&second).is_empty());
+
+        let third = game.new_actor(&pusher, IVec2::new(3, 0)).unwrap();
+        assert_eq!(game.merge_copies(&third), vec![first, second]);
+        let view = game.actor_view(&third).unwrap();
+        let upgrade = game.actor_types().upgrade(&pusher).unwrap();
+        assert_eq!(view.actor.tier, 2);
+        assert_eq!(view.actor_type.prize, upgrade.prize);
+        assert_eq!(view.actor.activations_left, upgrade.max_activations);
+        assert_eq!(game.board().actor_ids().count(), 2);
+    }
+
+    #[test]
+    fn merging_needs_an_upgrade_and_a_lower_tier() {
+        let config = test_utils::game_config();
+        let mut game = Game::with_seed(&config.game, test_utils::actor_types(), 0);
+        let bomb = ActorTypeId::new("bomb".to_string());
+        assert!(game.actor_types().upgrade(&bomb).is_none());
+        let bombs: Vec<_> = (1..=MERGE_COUNT as i32)
+            .map(|x| game.new_actor(&bomb, IVec2::new(x, 0)).unwrap())
+            .collect();
+        assert!(game.merge_copies(bombs.last().unwrap()).is_empty());
+
+        let pusher = ActorTypeId::new("pusher".to_string());
+        let pushers: Vec<_> = (1..=MERGE_COUNT as i32)
+            .map(|x| game.new_actor(&pusher, IVec2::new(x, 1)).unwrap())
+            .collect();
+        for actor_id in &pushers {
+            game.update_actor(actor_id, |actor| actor.tier = MAX_TIER);
+        }
+        assert!(game.merge_copies(pushers.last().unwrap()).is_empty());
+        assert_eq!(game.board().actor_ids().count(), 1 + 2 * MERGE_COUNT);
+    }
+
+    fn stocks_for_seed(seed: u64) -> Vec<Vec<ActorTypeId>> {
+        let config = test_utils::game_config();
+        let actor_types = test_utils::actor_types();
+        let mut game = Game::with_seed(&config.game, actor_types, seed);
+        game.set_gold(1000);
+        (0..5)
+            .map(|_| {
+                game.restock();
+                game.shop().stock().cloned().collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn same_seed_same_restocks() {
+        assert_eq!(stocks_for_seed(42), stocks_for_seed(42));
     }
 }
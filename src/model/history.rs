@@ -0,0 +1,152 @@
+//! Undo/redo over whole game states. Snapshots are cheap, since the board
+//! uses persistent maps.
+
+use bevy::prelude::*;
+
+use crate::model::{actor_type::ActorTypeId, game::Game, shop::ShopSlot};
+
+/// Older states are dropped past this many.
+const MAX_UNDO: usize = 100;
+
+#[derive(Resource, Debug, Clone, Default)]
+pub struct History {
+    undo: Vec<Game>,
+    redo: Vec<Game>,
+}
+
+impl History {
+    /// Records the state from before an action, which can then be undone.
+    pub fn record(&mut self, before: Game) {
+        if self.undo.len() == MAX_UNDO {
+            self.undo.remove(0);
+        }
+        self.undo.push(before);
+        self.redo.clear();
+    }
+
+    /// Returns the state to go back to.
+    pub fn undo(&mut self, current: &Game) -> Option<Game> {
+        let previous = self.undo.pop()?;
+        self.redo.push(current.clone());
+        Some(previous)
+    }
+
+    /// Returns the state to go forward to.
+    pub fn redo(&mut self, current: &Game) -> Option<Game> {
+        let next = self.redo.pop()?;
+        self.undo.push(current.clone());
+        Some(next)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+/// A shop item bought and on its way to the board.
+#[derive(Debug, Clone)]
+pub struct Purchase {
+    actor_type_id: ActorTypeId,
+    slot: ShopSlot,
+    /// The state from before the purchase, which undoing the placement goes back to.
+    before: Game,
+}
+
+impl Purchase {
+    /// Buys the shop item at `index`, keeping the state from before.
+    pub fn buy(game: &mut Game, actor_type_id: &ActorTypeId, index: usize) -> Option<Self> {
+        let before = game.clone();
+        let slot = game.buy_item(actor_type_id, index)?;
+        Some(Self {
+            actor_type_id: actor_type_id.clone(),
+            slot,
+            before,
+        })
+    }
+
+    pub fn actor_type_id(&self) -> &ActorTypeId {
+        &self.actor_type_id
+    }
+
+    /// Puts the item back in its slot, refunded.
+    pub fn cancel(&self, game: &mut Game) {
+        game.return_item(&self.actor_type_id, self.slot);
+    }
+
+    /// The item went on the board; undoing that goes back to before the purchase.
+    pub fn place(&self, history: &mut History) {
+        history.record(self.before.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use super::*;
+    use crate::model::test_utils;
+
+    #[test]
+    fn undoing_a_purchase_refunds_it() {
+        let mut game = Game::with_seed(
+            &test_utils::game_config().game,
+            test_utils::actor_types(),
+            0,
+        );
+        game.set_gold(100);
+        game.restock();
+        game.toggle_shop_lock(1);
+        let gold = game.gold();
+        let stock: Vec<_> = game.shop().stock().cloned().collect();
+        let mut history = History::default();
+
+        // bought from a locked slot, then dropped on the board
+        let bought = stock[1].clone();
+        let purchase = Purchase::buy(&mut game, &bought, 1).unwrap();
+        game.new_actor(&bought, IVec2::new(1, 0)).unwrap();
+        purchase.place(&mut history);
+        let after_gold = game.gold();
+
+        let game = history.undo(&game).unwrap();
+        assert_eq!(game.gold(), gold);
+        assert_eq!(game.shop().stock().cloned().collect::<Vec<_>>(), stock);
+        assert!(game.shop().is_locked(1));
+        assert_eq!(game.board().actor_ids().count(), 1);
+        assert!(!history.can_undo());
+
+        let game = history.redo(&game).unwrap();
+        assert_eq!(game.gold(), after_gold);
+        assert_eq!(game.board().actor_ids().count(), 2);
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn cancelling_a_purchase_keeps_its_lock() {
+        let mut game = Game::with_seed(
+            &test_utils::game_config().game,
+            test_utils::actor_types(),
+            0,
+        );
+        game.set_gold(100);
+        game.restock();
+        game.toggle_shop_lock(2);
+        let stock: Vec<_> = game.shop().stock().cloned().collect();
+        let stats = game.stats().clone();
+
+        let purchase = Purchase::buy(&mut game, &stock[2], 2).unwrap();
+        purchase.cancel(&mut game);
+        assert_eq!(game.gold(), 100);
+        assert_eq!(game.stats(), &stats);
+        assert_eq!(game.shop().stock().cloned().collect::<Vec<_>>(), stock);
+        assert!(game.shop().is_locked(2));
+    }
+}
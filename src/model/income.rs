@@ -0,0 +1,77 @@
+//! Gold earned at the end of a turn, on top of the prizes.
+
+/// Taken from the game config. The default gives no income, like runs saved
+/// before it existed.
+#[derive(Debug, Clone, Copy, PartialEq, Default, serde::Serialize, serde::Deserialize)]
+pub struct IncomeRules {
+    /// One gold of interest for every that much banked gold, none if 0.
+    pub interest_per: u64,
+    pub max_interest: u64,
+    /// Given every turn.
+    pub stipend: u64,
+    /// A turn earning at least this much in prizes extends the streak.
+    pub streak_threshold: u64,
+    /// Given for each turn of the streak after the first.
+    pub streak_bonus: u64,
+    pub max_streak_bonus: u64,
+}
+
+/// Where the gold of a turn came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub struct Income {
+    pub prizes: u64,
+    pub interest: u64,
+    pub stipend: u64,
+    pub streak: u64,
+}
+
+impl Income {
+    /// Everything but the prizes, which are already in the gold.
+    pub fn bonus(&self) -> u64 {
+        self.interest + self.stipend + self.streak
+    }
+}
+
+impl IncomeRules {
+    /// `streak` already counts the turn being evaluated.
+    pub fn evaluate(&self, gold: u64, prizes: u64, streak: u32) -> Income {
+        let interest = match self.interest_per {
+            0 => 0,
+            interest_per => (gold / interest_per).min(self.max_interest),
+        };
+        let streak =
+            (self.streak_bonus * streak.saturating_sub(1) as u64).min(self.max_streak_bonus);
+        Income {
+            prizes,
+            interest,
+            stipend: self.stipend,
+            streak,
+        }
+    }
+
+    pub fn extends_streak(&self, prizes: u64) -> bool {
+        prizes >= self.streak_threshold
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn income_is_capped() {
+        let rules = IncomeRules {
+            interest_per: 5,
+            max_interest: 3,
+            stipend: 1,
+            streak_threshold: 4,
+            streak_bonus: 2,
+            max_streak_bonus: 5,
+        };
+        assert_eq!(rules.evaluate(12, 4, 1).bonus(), 2 + 1);
+        assert_eq!(rules.evaluate(100, 4, 3).interest, 3);
+        assert_eq!(rules.evaluate(0, 4, 3).streak, 4);
+        assert_eq!(rules.evaluate(0, 4, 10).streak, 5);
+        assert!(!rules.extends_streak(3));
+    }
+}
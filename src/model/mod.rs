@@ -4,6 +4,16 @@ pub mod actor_types;
 pub mod board;
 pub mod direction;
 pub mod game;
+pub mod history;
+pub mod income;
 pub mod program;
+pub mod replay;
+pub mod rng;
+pub mod rounds;
 pub mod runner;
+pub mod save;
 pub mod shop;
+pub mod stats;
+
+#[cfg(test)]
+pub(crate) mod test_utils;
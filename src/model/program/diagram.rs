@@ -0,0 +1,152 @@
+use bevy::prelude::*;
+
+use crate::model::{
+    direction::{Dir, RelDir},
+    program::{Action, Program},
+};
+
+/// Repeats are drawn at most this many times, so that the diagram stays small.
+const MAX_REPEATS: u32 = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DiagramMark {
+    /// The actor stepped on that tile.
+    Path,
+    Hit,
+    Push,
+    Spawn,
+    Destroy,
+}
+
+/// Where a program acts, relative to its actor standing at the origin and
+/// looking right. Both branches of conditions are drawn; a repeat until
+/// blocked is drawn once.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ProgramDiagram {
+    pub marks: Vec<(IVec2, DiagramMark)>,
+}
+
+impl ProgramDiagram {
+    pub fn new(program: &Program) -> Self {
+        let mut diagram = Self::default();
+        diagram.trace(program.iter(), IVec2::ZERO, Dir::Right);
+        diagram
+    }
+
+    /// The marks on that tile, in the order the program made them.
+    pub fn marks_at(&self, coord: IVec2) -> impl Iterator<Item = DiagramMark> + '_ {
+        self.marks
+            .iter()
+            .filter(move |(mark_coord, _)| *mark_coord == coord)
+            .map(|(_, mark)| *mark)
+    }
+
+    /// The smallest and largest corners of the marked area, origin included.
+    pub fn bounds(&self) -> (IVec2, IVec2) {
+        self.marks
+            .iter()
+            .fold((IVec2::ZERO, IVec2::ZERO), |(min, max), (coord, _)| {
+                (min.min(*coord), max.max(*coord))
+            })
+    }
+
+    fn mark(&mut self, coord: IVec2, mark: DiagramMark) {
+        if !self.marks.contains(&(coord, mark)) {
+            self.marks.push((coord, mark));
+        }
+    }
+
+    /// Returns where the actor ends up, and where it looks.
+    fn trace<'a>(
+        &mut self,
+        actions: impl Iterator<Item = &'a Action>,
+        mut coord: IVec2,
+        mut looks_to: Dir,
+    ) -> (IVec2, Dir) {
+        for action in actions {
+            (coord, looks_to) = match action {
+                Action::Forward => {
+                    let coord = looks_to.apply_to(coord);
+                    self.mark(coord, DiagramMark::Path);
+                    (coord, looks_to)
+                }
+                Action::Push(rel_dir) => {
+                    let target = looks_to.apply_relative(*rel_dir).apply_to(coord);
+                    self.mark(target, DiagramMark::Push);
+                    (coord, looks_to)
+                }
+                Action::Pull(rel_dir) => {
+                    let back_dir = looks_to
+                        .apply_relative(*rel_dir)
+                        .apply_relative(RelDir::Back);
+                    let coord = back_dir.apply_to(coord);
+                    self.mark(coord, DiagramMark::Path);
+                    (coord, looks_to)
+                }
+                Action::Turn(rel_dir) => (coord, looks_to.apply_relative(*rel_dir)),
+                Action::Hit(offsets) => {
+                    for offset in offsets {
+                        self.mark(
+                            looks_to.rel_coord_to_coord(coord, *offset),
+                            DiagramMark::Hit,
+                        );
+                    }
+                    (coord, looks_to)
+                }
+                Action::Spawn { offset, .. } => {
+                    self.mark(
+                        looks_to.rel_coord_to_coord(coord, *offset),
+                        DiagramMark::Spawn,
+                    );
+                    (coord, looks_to)
+                }
+                Action::Destroy { offset, .. } => {
+                    self.mark(
+                        looks_to.rel_coord_to_coord(coord, *offset),
+                        DiagramMark::Destroy,
+                    );
+                    (coord, looks_to)
+                }
+                // The actor goes on from where the `then` branch left it.
+                Action::IfBlocked {
+                    then, otherwise, ..
+                }
+                | Action::IfEmpty {
+                    then, otherwise, ..
+                } => {
+                    self.trace(otherwise.iter(), coord, looks_to);
+                    self.trace(then.iter(), coord, looks_to)
+                }
+                Action::Repeat { times, actions } => {
+                    for _ in 0..(*times).min(MAX_REPEATS) {
+                        (coord, looks_to) = self.trace(actions.iter(), coord, looks_to);
+                    }
+                    (coord, looks_to)
+                }
+                Action::RepeatUntilBlocked(actions) => self.trace(actions.iter(), coord, looks_to),
+            };
+        }
+        (coord, looks_to)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::program::parse_program;
+
+    #[test]
+    fn traces_moves_and_hits() {
+        let program = parse_program("repeat 2 { forward }; turn left; hit (1, 0)").unwrap();
+        let diagram = ProgramDiagram::new(&program);
+        assert_eq!(
+            diagram.marks,
+            vec![
+                (IVec2::new(1, 0), DiagramMark::Path),
+                (IVec2::new(2, 0), DiagramMark::Path),
+                (IVec2::new(2, 1), DiagramMark::Hit),
+            ]
+        );
+        assert_eq!(diagram.bounds(), (IVec2::ZERO, IVec2::new(2, 1)));
+    }
+}
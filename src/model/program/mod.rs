@@ -0,0 +1,168 @@
+use std::fmt;
+
+use bevy::prelude::*;
+use serde::de::{self, SeqAccess, Visitor};
+
+use crate::model::{actor_type::ActorTypeId, direction::RelDir};
+
+mod diagram;
+mod parsing;
+
+pub use diagram::{DiagramMark, ProgramDiagram};
+pub use parsing::{ProgramParseError, parse_program};
+
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(tag = "type", content = "arg")]
+pub enum Action {
+    Forward,
+    Push(RelDir),
+    /// Steps away from that direction, dragging the adjacent line of actors along.
+    Pull(RelDir),
+    Turn(RelDir),
+    Hit(Vec<IVec2>),
+    /// Creates an actor on an empty tile, looking the same way as its spawner.
+    /// Unless `persistent`, it is gone when the turn ends.
+    Spawn {
+        actor_type: ActorTypeId,
+        offset: IVec2,
+        #[serde(default)]
+        persistent: bool,
+    },
+    /// Removes the actor on that tile, the start actor excepted.
+    /// Unless `persistent`, it is back when the turn ends.
+    Destroy {
+        offset: IVec2,
+        #[serde(default)]
+        persistent: bool,
+    },
+    /// Blocked means there is an actor on the next tile in that direction.
+    IfBlocked {
+        dir: RelDir,
+        then: Vec<Action>,
+        #[serde(rename = "else", default)]
+        otherwise: Vec<Action>,
+    },
+    /// The offset is relative to the actor, like `Hit` offsets.
+    IfEmpty {
+        offset: IVec2,
+        then: Vec<Action>,
+        #[serde(rename = "else", default)]
+        otherwise: Vec<Action>,
+    },
+    Repeat {
+        times: u32,
+        actions: Vec<Action>,
+    },
+    /// Runs until the tile in front of the actor is blocked.
+    RepeatUntilBlocked(Vec<Action>),
+}
+
+impl Action {
+    /// The actions nested in this one, if it is a block.
+    pub fn children(&self) -> impl Iterator<Item = &Action> {
+        let (first, second): (&[Action], &[Action]) = match self {
+            Action::IfBlocked {
+                then, otherwise, ..
+            }
+            | Action::IfEmpty {
+                then, otherwise, ..
+            } => (then, otherwise),
+            Action::Repeat { actions, .. } | Action::RepeatUntilBlocked(actions) => (actions, &[]),
+            Action::Forward
+            | Action::Push(_)
+            | Action::Pull(_)
+            | Action::Turn(_)
+            | Action::Hit(_)
+            | Action::Spawn { .. }
+            | Action::Destroy { .. } => (&[], &[]),
+        };
+        first.iter().chain(second)
+    }
+}
+
+/// Deserializes either from a list of actions, or from the text form
+/// (`"turn left; push right; turn back; forward"`).
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Program(pub Vec<Action>);
+
+impl Program {
+    pub fn iter(&self) -> impl Iterator<Item = &Action> {
+        self.0.iter()
+    }
+
+    /// Every action, blocks included, depth first.
+    pub fn walk(&self) -> Vec<&Action> {
+        fn walk_into<'a>(action: &'a Action, result: &mut Vec<&'a Action>) {
+            result.push(action);
+            for child in action.children() {
+                walk_into(child, result);
+            }
+        }
+        let mut result = Vec::new();
+        for action in self.iter() {
+            walk_into(action, &mut result);
+        }
+        result
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for Program {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(ProgramVisitor)
+    }
+}
+
+struct ProgramVisitor;
+
+impl<'de> Visitor<'de> for ProgramVisitor {
+    type Value = Program;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("a program string or a list of actions")
+    }
+
+    fn visit_str<E: de::Error>(self, text: &str) -> Result<Program, E> {
+        parse_program(text).map_err(E::custom)
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, seq: A) -> Result<Program, A::Error> {
+        let actions: Vec<Action> =
+            serde::Deserialize::deserialize(de::value::SeqAccessDeserializer::new(seq))?;
+        Ok(Program(actions))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(serde::Deserialize)]
+    struct Holder {
+        program: Program,
+    }
+
+    fn program(toml_text: &str) -> Result<Program, toml::de::Error> {
+        toml::from_str::<Holder>(toml_text).map(|holder| holder.program)
+    }
+
+    #[test]
+    fn text_and_array_forms_match() {
+        let text = program(r#"program = "turn left; push right; turn back; forward; hit (1, 0)""#);
+        let array = program(
+            r#"program = [
+                { type = "Turn", arg = "Left" },
+                { type = "Push", arg = "Right" },
+                { type = "Turn", arg = "Back" },
+                { type = "Forward" },
+                { type = "Hit", arg = [[1, 0]] },
+            ]"#,
+        );
+        assert_eq!(text.unwrap(), array.unwrap());
+    }
+
+    #[test]
+    fn text_errors_carry_position() {
+        let err = program("program = \"forward\\npush up\"").unwrap_err();
+        assert!(err.message().contains("line 2, column 6"), "{err}");
+    }
+}
@@ -0,0 +1,431 @@
+// Text form of a program, as an alternative to the array form in actor type files.
+//
+// Example text:
+// turn left; push right; pull back
+// turn back; forward
+// hit (1, 0), (-1, 0)
+// repeat until blocked { forward }
+// if blocked left { turn right } else { repeat 2 { push front } }
+// if empty (1, 1) { forward }
+// spawn bowling_ball (1, 0); destroy (0, 0) persistent
+//
+// Actions are separated by `;` or new lines. Line and column in errors are
+// counted from the start of the program string.
+use bevy::math::IVec2;
+use nom::{
+    IResult, Parser,
+    branch::alt,
+    bytes::complete::{tag, take_while, take_while1},
+    character::complete::{alpha1, char, i32, multispace0, space0, space1, u32},
+    combinator::{cut, eof, opt},
+    error::{ErrorKind, ParseError},
+    multi::separated_list1,
+    sequence::{delimited, preceded, separated_pair},
+};
+use thiserror::Error;
+
+use crate::model::{
+    actor_type::ActorTypeId,
+    direction::RelDir,
+    program::{Action, Program},
+};
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("line {line}, column {column}: expected {expected}")]
+pub struct ProgramParseError {
+    pub line: usize,
+    pub column: usize,
+    pub expected: &'static str,
+}
+
+impl ProgramParseError {
+    fn at(text: &str, rest: &str, expected: &'static str) -> Self {
+        let consumed = &text[..text.len() - rest.len()];
+        let line = consumed.matches('\n').count() + 1;
+        let column = consumed.rsplit('\n').next().unwrap_or("").chars().count() + 1;
+        Self {
+            line,
+            column,
+            expected,
+        }
+    }
+}
+
+/// Where parsing failed, and what was expected there.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Expected<'a> {
+    input: &'a str,
+    what: &'static str,
+}
+
+impl<'a> ParseError<&'a str> for Expected<'a> {
+    fn from_error_kind(input: &'a str, _kind: ErrorKind) -> Self {
+        Self {
+            input,
+            what: "valid syntax",
+        }
+    }
+
+    fn append(_input: &'a str, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+type PResult<'a, O> = IResult<&'a str, O, Expected<'a>>;
+
+const ACTION: &str = "an action (forward, push, pull, turn, hit, spawn, destroy, if or repeat)";
+const ACTOR_TYPE: &str = "an actor type";
+const CONDITION: &str = "a condition (blocked or empty)";
+const REPEAT_COUNT: &str = "a count or `until blocked`";
+const BLOCK_START: &str = "`{`";
+const BLOCK_END: &str = "`}`";
+const DIRECTION: &str = "a direction (front, back, left or right)";
+const OFFSET: &str = "an offset like (1, 0)";
+const END_OF_ACTION: &str = "`;`, a new line or the end of the program";
+
+pub fn parse_program(text: &str) -> Result<Program, ProgramParseError> {
+    match (actions, expect(END_OF_ACTION, eof)).parse(text) {
+        Ok((_rest, (actions, _eof))) => Ok(Program(actions)),
+        Err(nom::Err::Error(err) | nom::Err::Failure(err)) => {
+            Err(ProgramParseError::at(text, err.input, err.what))
+        }
+        Err(nom::Err::Incomplete(_)) => unreachable!("only complete parsers are used"),
+    }
+}
+
+/// Reports any failure of `parser` as `what` being expected where it started.
+fn expect<'a, O>(
+    what: &'static str,
+    mut parser: impl Parser<&'a str, Output = O, Error = Expected<'a>>,
+) -> impl FnMut(&'a str) -> PResult<'a, O> {
+    move |input| {
+        parser
+            .parse(input)
+            .map_err(|err| err.map(|_| Expected { input, what }))
+    }
+}
+
+fn is_separator(c: char) -> bool {
+    c == ';' || c.is_whitespace()
+}
+
+fn actions(input: &str) -> PResult<'_, Vec<Action>> {
+    let mut actions = Vec::new();
+    let (mut input, _) = take_while(is_separator).parse(input)?;
+    while !input.is_empty() && !input.starts_with('}') {
+        let (rest, action) = action(input)?;
+        actions.push(action);
+        let (rest, _) = space0(rest)?;
+        // Anything but a separator ends the list, the caller decides if that's fine.
+        match take_while1::<_, _, Expected>(|c| c == ';' || c == '\n' || c == '\r')(rest) {
+            Ok((rest, _)) => (input, _) = take_while(is_separator).parse(rest)?,
+            Err(_) => return Ok((rest, actions)),
+        }
+    }
+    Ok((input, actions))
+}
+
+fn action(input: &str) -> PResult<'_, Action> {
+    let (rest, keyword) = expect(ACTION, alpha1).parse(input)?;
+    match keyword {
+        "forward" => Ok((rest, Action::Forward)),
+        "push" => preceded(space0, rel_dir).map(Action::Push).parse(rest),
+        "pull" => preceded(space0, rel_dir).map(Action::Pull).parse(rest),
+        "turn" => preceded(space0, rel_dir).map(Action::Turn).parse(rest),
+        "hit" => preceded(space0, offsets).map(Action::Hit).parse(rest),
+        "spawn" => (
+            preceded(space0, actor_type),
+            preceded(space0, offset),
+            persistent,
+        )
+            .map(|(actor_type, offset, persistent)| Action::Spawn {
+                actor_type,
+                offset,
+                persistent,
+            })
+            .parse(rest),
+        "destroy" => (preceded(space0, offset), persistent)
+            .map(|(offset, persistent)| Action::Destroy { offset, persistent })
+            .parse(rest),
+        "if" => preceded(space0, if_action).parse(rest),
+        "repeat" => preceded(space0, repeat_action).parse(rest),
+        _ => Err(nom::Err::Failure(Expected {
+            input,
+            what: ACTION,
+        })),
+    }
+}
+
+fn actor_type(input: &str) -> PResult<'_, ActorTypeId> {
+    expect(
+        ACTOR_TYPE,
+        take_while1(|c: char| c.is_ascii_alphanumeric() || c == '_'),
+    )
+    .map(|name: &str| ActorTypeId::new(name.to_string()))
+    .parse(input)
+}
+
+/// The optional `persistent` flag of board changes.
+fn persistent(input: &str) -> PResult<'_, bool> {
+    opt(preceded(space1, tag("persistent")))
+        .map(|flag| flag.is_some())
+        .parse(input)
+}
+
+fn if_action(input: &str) -> PResult<'_, Action> {
+    let (rest, condition) = expect(CONDITION, alpha1).parse(input)?;
+    match condition {
+        "blocked" => (preceded(space0, rel_dir), block, else_block)
+            .map(|(dir, then, otherwise)| Action::IfBlocked {
+                dir,
+                then,
+                otherwise,
+            })
+            .parse(rest),
+        "empty" => (preceded(space0, offset), block, else_block)
+            .map(|(offset, then, otherwise)| Action::IfEmpty {
+                offset,
+                then,
+                otherwise,
+            })
+            .parse(rest),
+        _ => Err(nom::Err::Failure(Expected {
+            input,
+            what: CONDITION,
+        })),
+    }
+}
+
+fn repeat_action(input: &str) -> PResult<'_, Action> {
+    let until_blocked = (tag("until"), space1, tag("blocked"));
+    let (rest, times) = expect(
+        REPEAT_COUNT,
+        alt((until_blocked.map(|_| None), u32.map(Some))),
+    )
+    .parse(input)?;
+    let (rest, actions) = block(rest)?;
+    let action = match times {
+        Some(times) => Action::Repeat { times, actions },
+        None => Action::RepeatUntilBlocked(actions),
+    };
+    Ok((rest, action))
+}
+
+/// `{ actions }`, possibly spanning several lines.
+fn block(input: &str) -> PResult<'_, Vec<Action>> {
+    delimited(
+        preceded(space0, expect(BLOCK_START, char('{'))),
+        actions,
+        expect(BLOCK_END, char('}')),
+    )
+    .parse(input)
+}
+
+fn else_block(input: &str) -> PResult<'_, Vec<Action>> {
+    let (rest, has_else) = opt((multispace0, tag("else"))).parse(input)?;
+    match has_else {
+        Some(_) => block(rest),
+        None => Ok((input, Vec::new())),
+    }
+}
+
+fn rel_dir(input: &str) -> PResult<'_, RelDir> {
+    let (rest, word) = expect(DIRECTION, alpha1).parse(input)?;
+    let rel_dir = match word {
+        "front" => RelDir::Front,
+        "back" => RelDir::Back,
+        "left" => RelDir::Left,
+        "right" => RelDir::Right,
+        _ => {
+            return Err(nom::Err::Failure(Expected {
+                input,
+                what: DIRECTION,
+            }));
+        }
+    };
+    Ok((rest, rel_dir))
+}
+
+fn offsets(input: &str) -> PResult<'_, Vec<IVec2>> {
+    separated_list1((space0, char(','), space0), cut(offset)).parse(input)
+}
+
+fn offset(input: &str) -> PResult<'_, IVec2> {
+    expect(
+        OFFSET,
+        delimited(
+            (char('('), space0),
+            separated_pair(i32, (space0, char(','), space0), i32),
+            (space0, char(')')),
+        ),
+    )
+    .map(|(x, y)| IVec2::new(x, y))
+    .parse(input)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(text: &str) -> Result<Vec<Action>, ProgramParseError> {
+        parse_program(text).map(|program| program.0)
+    }
+
+    #[test]
+    fn test_parse_actions() {
+        assert_eq!(
+            parse("turn left; push right; turn back; forward; pull front"),
+            Ok(vec![
+                Action::Turn(RelDir::Left),
+                Action::Push(RelDir::Right),
+                Action::Turn(RelDir::Back),
+                Action::Forward,
+                Action::Pull(RelDir::Front),
+            ])
+        );
+        assert_eq!(
+            parse("hit (1, 0), (-1,0)"),
+            Ok(vec![Action::Hit(vec![IVec2::new(1, 0), IVec2::new(-1, 0)])])
+        );
+        assert_eq!(parse(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_parse_board_changes() {
+        assert_eq!(
+            parse("spawn hammer_time (1, 0); destroy (0, 0) persistent"),
+            Ok(vec![
+                Action::Spawn {
+                    actor_type: ActorTypeId::new("hammer_time".to_string()),
+                    offset: IVec2::new(1, 0),
+                    persistent: false,
+                },
+                Action::Destroy {
+                    offset: IVec2::new(0, 0),
+                    persistent: true,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_blocks() {
+        assert_eq!(
+            parse("repeat 3 { forward }; hit (1, 0)"),
+            Ok(vec![
+                Action::Repeat {
+                    times: 3,
+                    actions: vec![Action::Forward]
+                },
+                Action::Hit(vec![IVec2::new(1, 0)]),
+            ])
+        );
+        assert_eq!(
+            parse("repeat until blocked {\n  forward\n  turn left\n}"),
+            Ok(vec![Action::RepeatUntilBlocked(vec![
+                Action::Forward,
+                Action::Turn(RelDir::Left)
+            ])])
+        );
+        assert_eq!(
+            parse("if blocked left { turn right }\nelse {\n  if empty (1, 1) { forward }\n}"),
+            Ok(vec![Action::IfBlocked {
+                dir: RelDir::Left,
+                then: vec![Action::Turn(RelDir::Right)],
+                otherwise: vec![Action::IfEmpty {
+                    offset: IVec2::new(1, 1),
+                    then: vec![Action::Forward],
+                    otherwise: vec![],
+                }],
+            }])
+        );
+        assert_eq!(
+            parse("if empty (0, 1) {}\nforward"),
+            Ok(vec![
+                Action::IfEmpty {
+                    offset: IVec2::new(0, 1),
+                    then: vec![],
+                    otherwise: vec![],
+                },
+                Action::Forward
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_separators() {
+        assert_eq!(
+            parse("\n  forward\n\n  turn right ;\n"),
+            Ok(vec![Action::Forward, Action::Turn(RelDir::Right)])
+        );
+        assert_eq!(parse("forward;;forward"), parse("forward\nforward"));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse("forward; jump"),
+            Err(ProgramParseError {
+                line: 1,
+                column: 10,
+                expected: ACTION
+            })
+        );
+        assert_eq!(
+            parse("forward\npush up"),
+            Err(ProgramParseError {
+                line: 2,
+                column: 6,
+                expected: DIRECTION
+            })
+        );
+        assert_eq!(
+            parse("hit (1, 0), 2"),
+            Err(ProgramParseError {
+                line: 1,
+                column: 13,
+                expected: OFFSET
+            })
+        );
+        assert_eq!(
+            parse("forward forward"),
+            Err(ProgramParseError {
+                line: 1,
+                column: 9,
+                expected: END_OF_ACTION
+            })
+        );
+        assert_eq!(
+            parse("repeat 2 {\n  forward"),
+            Err(ProgramParseError {
+                line: 2,
+                column: 10,
+                expected: BLOCK_END
+            })
+        );
+        assert_eq!(
+            parse("if full front { forward }"),
+            Err(ProgramParseError {
+                line: 1,
+                column: 4,
+                expected: CONDITION
+            })
+        );
+        assert_eq!(
+            parse("repeat forever { forward }"),
+            Err(ProgramParseError {
+                line: 1,
+                column: 8,
+                expected: REPEAT_COUNT
+            })
+        );
+        assert_eq!(
+            parse("turn"),
+            Err(ProgramParseError {
+                line: 1,
+                column: 5,
+                expected: DIRECTION
+            })
+        );
+    }
+}
@@ -0,0 +1,118 @@
+//! Recorded turns, to be played back or shared as a RON file.
+
+use bevy::prelude::*;
+use thiserror::Error;
+
+use crate::model::{actor_types::ActorTypes, game::Game, runner::Cmd};
+
+/// Bump this whenever the saved shape of [`TurnRecord`] changes.
+pub const REPLAY_VERSION: u32 = 1;
+
+/// Everything needed to play a turn again: the game as it was when the turn
+/// started, and the commands the runner produced for it.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct TurnRecord {
+    pub start: Game,
+    pub cmds: Vec<Cmd>,
+}
+
+impl TurnRecord {
+    /// The starting game, against the actor types currently loaded.
+    pub fn start_game(&self, actor_types: ActorTypes) -> Game {
+        let mut game = self.start.clone();
+        game.attach_actor_types(actor_types);
+        game
+    }
+}
+
+/// The turns of the current run, oldest first.
+#[derive(Resource, Debug, Clone, Default)]
+pub struct RunLog {
+    turns: Vec<TurnRecord>,
+}
+
+impl RunLog {
+    pub fn record(&mut self, start: Game, cmds: Vec<Cmd>) {
+        self.turns.push(TurnRecord { start, cmds });
+    }
+
+    pub fn last_turn(&self) -> Option<&TurnRecord> {
+        self.turns.last()
+    }
+
+    pub fn clear(&mut self) {
+        self.turns.clear();
+    }
+}
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct ReplayFile {
+    pub version: u32,
+    pub turn: TurnRecord,
+}
+
+#[derive(serde::Deserialize)]
+struct ReplayHeader {
+    version: u32,
+}
+
+#[derive(Error, Debug)]
+pub enum ReplayError {
+    #[error("could not parse replay: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not write replay: {0}")]
+    Write(#[from] ron::Error),
+    #[error("unsupported replay version {0} (current is {REPLAY_VERSION})")]
+    UnsupportedVersion(u32),
+}
+
+impl ReplayFile {
+    pub fn new(turn: &TurnRecord) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            turn: turn.clone(),
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, ReplayError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, ReplayError> {
+        let header: ReplayHeader = ron::from_str(text)?;
+        match header.version {
+            REPLAY_VERSION => Ok(ron::from_str(text)?),
+            version => Err(ReplayError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use super::*;
+    use crate::model::{actor_type::ActorTypeId, runner::Runner, test_utils};
+
+    #[test]
+    fn round_trip() {
+        let actor_types = test_utils::actor_types();
+        let mut game = Game::with_seed(&test_utils::game_config().game, actor_types.clone(), 3);
+        game.new_actor(&ActorTypeId::new("pusher".to_string()), IVec2::new(1, 0))
+            .unwrap();
+        let (_end_game, cmds) = Runner::new(game.clone()).run();
+        let mut run_log = RunLog::default();
+        run_log.record(game, cmds);
+
+        let turn = run_log.last_turn().unwrap();
+        let text = ReplayFile::new(turn).to_ron().unwrap();
+        let restored = ReplayFile::from_ron(&text).unwrap().turn;
+        assert_eq!(format!("{:?}", restored.cmds), format!("{:?}", turn.cmds));
+        // playing the restored start again gives the same commands
+        let (_end_game, cmds) = Runner::new(restored.start_game(actor_types)).run();
+        assert_eq!(format!("{cmds:?}"), format!("{:?}", turn.cmds));
+    }
+}
@@ -0,0 +1,50 @@
+use rand::{RngCore, SeedableRng};
+use rand_pcg::Pcg32;
+
+/// The random number generator owned by a [`Game`](super::game::Game).
+///
+/// Every random decision of a run draws from it, so the seed and the player's
+/// inputs are enough to reproduce the run. Its state is part of a save.
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct GameRng {
+    seed: u64,
+    rng: Pcg32,
+}
+
+impl GameRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: Pcg32::seed_from_u64(seed),
+        }
+    }
+
+    /// The seed this generator started from, not its current state.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(0)
+    }
+}
+
+impl RngCore for GameRng {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
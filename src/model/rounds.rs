@@ -0,0 +1,181 @@
+//! The round schedule: modifiers that change the rules of some rounds, and boss rounds.
+
+use bevy::prelude::*;
+use thiserror::Error;
+
+/// A change to the rules that lasts one round.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(tag = "type", content = "arg")]
+pub enum RoundModifier {
+    /// Each push takes an extra activation from the pusher.
+    PushesCostActivation,
+    /// Prizes are only paid on even turns of the round.
+    PrizesOnEvenTurns,
+    /// The round is that many turns shorter, down to 1 turn.
+    FewerTurns(u64),
+    /// No restocking and no buying.
+    ShopClosed,
+}
+
+impl RoundModifier {
+    pub fn description(&self) -> String {
+        match self {
+            RoundModifier::PushesCostActivation => "Pushes cost an activation".to_string(),
+            RoundModifier::PrizesOnEvenTurns => "Prizes only on even turns".to_string(),
+            RoundModifier::FewerTurns(1) => "One turn less".to_string(),
+            RoundModifier::FewerTurns(turns) => format!("{turns} turns less"),
+            RoundModifier::ShopClosed => "The shop is closed".to_string(),
+        }
+    }
+}
+
+/// The modifiers of the current round, asked by the [`Runner`](super::runner::Runner)
+/// and the [`Shop`](super::shop::Shop).
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RoundRules {
+    /// Only set on boss rounds.
+    pub boss: Option<String>,
+    pub modifiers: Vec<RoundModifier>,
+}
+
+impl RoundRules {
+    pub fn is_empty(&self) -> bool {
+        self.boss.is_none() && self.modifiers.is_empty()
+    }
+
+    pub fn pushes_cost_activation(&self) -> bool {
+        self.modifiers
+            .contains(&RoundModifier::PushesCostActivation)
+    }
+
+    /// `turn` counts from 1 at the start of the round.
+    pub fn pays_prizes_on(&self, turn: u64) -> bool {
+        turn.is_multiple_of(2) || !self.modifiers.contains(&RoundModifier::PrizesOnEvenTurns)
+    }
+
+    pub fn turns(&self, turns_per_round: u64) -> u64 {
+        let removed: u64 = self
+            .modifiers
+            .iter()
+            .map(|modifier| match modifier {
+                RoundModifier::FewerTurns(turns) => *turns,
+                _ => 0,
+            })
+            .sum();
+        turns_per_round.saturating_sub(removed).max(1)
+    }
+
+    pub fn shop_closed(&self) -> bool {
+        self.modifiers.contains(&RoundModifier::ShopClosed)
+    }
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ScheduledRound {
+    pub round: u32,
+    pub modifiers: Vec<RoundModifier>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct BossRound {
+    pub name: String,
+    pub modifiers: Vec<RoundModifier>,
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum RoundScheduleError {
+    #[error("there is a boss every {0} rounds, but no `[[bosses]]`")]
+    NoBosses(u32),
+    #[error("round {0}: rounds start at 1")]
+    RoundZero(u32),
+}
+
+/// Loaded from `all.rounds.toml`.
+#[derive(Debug, Clone, Default, serde::Deserialize, Resource, Asset, TypePath)]
+pub struct RoundSchedule {
+    /// Every that many rounds is a boss round, never if 0.
+    #[serde(default)]
+    pub boss_every: u32,
+    #[serde(default)]
+    pub rounds: Vec<ScheduledRound>,
+    /// Boss rounds go through these in order, then start over.
+    #[serde(default)]
+    pub bosses: Vec<BossRound>,
+}
+
+impl RoundSchedule {
+    pub fn rules(&self, round: u32) -> RoundRules {
+        let mut rules = RoundRules::default();
+        for scheduled in self
+            .rounds
+            .iter()
+            .filter(|scheduled| scheduled.round == round)
+        {
+            rules.modifiers.extend(scheduled.modifiers.iter().copied());
+        }
+        if self.boss_every > 0 && round.is_multiple_of(self.boss_every) && !self.bosses.is_empty() {
+            let index = (round / self.boss_every - 1) as usize % self.bosses.len();
+            let boss = &self.bosses[index];
+            rules.boss = Some(boss.name.clone());
+            rules.modifiers.extend(boss.modifiers.iter().copied());
+        }
+        rules
+    }
+
+    pub fn validate(&self) -> Vec<RoundScheduleError> {
+        let mut errors = Vec::new();
+        if self.boss_every > 0 && self.bosses.is_empty() {
+            errors.push(RoundScheduleError::NoBosses(self.boss_every));
+        }
+        for scheduled in &self.rounds {
+            if scheduled.round == 0 {
+                errors.push(RoundScheduleError::RoundZero(scheduled.round));
+            }
+        }
+        errors
+    }
+}
+
+#[derive(Resource)]
+#[allow(dead_code)]
+pub struct RoundScheduleHandle(pub Handle<RoundSchedule>);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_utils;
+
+    #[test]
+    fn shipped_schedule_is_valid() {
+        assert_eq!(test_utils::round_schedule().validate(), vec![]);
+    }
+
+    #[test]
+    fn bosses_come_back_in_order() {
+        let schedule: RoundSchedule = toml::from_str(
+            r#"
+            boss_every = 2
+
+            [[rounds]]
+            round = 1
+            modifiers = [{ type = "FewerTurns", arg = 1 }]
+
+            [[bosses]]
+            name = "First"
+            modifiers = [{ type = "ShopClosed" }]
+
+            [[bosses]]
+            name = "Second"
+            modifiers = [{ type = "PrizesOnEvenTurns" }]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(schedule.rules(1).turns(5), 4);
+        assert!(schedule.rules(3).is_empty());
+        let bosses = [2, 4, 6].map(|round| schedule.rules(round).boss.unwrap());
+        assert_eq!(bosses, ["First", "Second", "First"]);
+        assert!(schedule.rules(2).shop_closed());
+        assert!(!schedule.rules(4).pays_prizes_on(1));
+        assert!(schedule.rules(4).pays_prizes_on(2));
+    }
+}
@@ -1,12 +1,67 @@
-use bevy::math::IVec2;
-#[allow(unused_imports)]
+use std::{
+    collections::VecDeque,
+    hash::{DefaultHasher, Hash, Hasher},
+};
+
+use bevy::{math::IVec2, platform::collections::HashSet};
 use tracing::warn;
 
-use crate::model::{
-    actor::ActorId, board::Board, direction::Dir, direction::RelDir, game::Game, program::Action,
+use crate::{
+    data::game_config::GameConfig,
+    model::{
+        actor::{Actor, ActorId},
+        actor_type::ActorTypeId,
+        board::Board,
+        direction::Dir,
+        direction::RelDir,
+        game::Game,
+        program::Action,
+    },
 };
 
-#[derive(Debug, Clone, Default)]
+/// How many actions a single activation may run, blocks and loop turns included.
+/// This is what stops a loop that never gets blocked.
+pub const MAX_ACTOR_STEPS: u32 = 64;
+
+/// Bounds on a whole turn, so that a runaway chain ends instead of freezing the game.
+#[derive(Debug, Clone, Copy)]
+pub struct TurnLimits {
+    pub max_commands: usize,
+    pub max_activations: usize,
+}
+
+impl Default for TurnLimits {
+    fn default() -> Self {
+        Self {
+            max_commands: 5000,
+            max_activations: 500,
+        }
+    }
+}
+
+/// In which order the actors hit during a turn get to run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Deserialize)]
+pub enum ActivationOrder {
+    /// The last activated actor runs first, so a chain is followed to its end
+    /// before its siblings run.
+    #[default]
+    DepthFirst,
+    /// Actors run in the order they were activated.
+    BreadthFirst,
+    /// Actors hit by the same wave are activated together once that wave is over,
+    /// then run one after the other.
+    Simultaneous,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum OverloadReason {
+    TooManyCommands,
+    TooManyActivations,
+    /// The board came back to a state it was already in.
+    Loop,
+}
+
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub enum Cmd {
     Activate(ActorId),
     Deactivate(ActorId),
@@ -19,9 +74,13 @@ pub enum Cmd {
     Done,
     Hit(Dest),
     Prize(ActorId, u64),
+    Spawn(ActorId, Actor),
+    Despawn(ActorId),
+    /// The turn was cut short, every remaining activation is dropped.
+    Overload(OverloadReason),
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct Dest {
     pub from_actor_id: ActorId,
     pub to_coord: IVec2,
@@ -31,7 +90,17 @@ pub struct Runner {
     saved_board: Board,
     game: Game,
     commands: Vec<Cmd>,
-    activated_actors: Vec<ActorId>,
+    activated_actors: VecDeque<ActorId>,
+    /// Only used by [`ActivationOrder::Simultaneous`]: actors hit by the current wave.
+    next_wave: Vec<ActorId>,
+    /// Only used by [`ActivationOrder::Simultaneous`]: actors of the current wave that
+    /// are done running, deactivated together with the rest of the wave.
+    finished_wave: Vec<ActorId>,
+    activation_order: ActivationOrder,
+    limits: TurnLimits,
+    activation_count: usize,
+    seen_states: HashSet<u64>,
+    overload: Option<OverloadReason>,
 }
 
 impl Runner {
@@ -40,32 +109,137 @@ impl Runner {
             saved_board: game.board().clone(),
             game,
             commands: Vec::new(),
-            activated_actors: Vec::new(),
+            activated_actors: VecDeque::new(),
+            next_wave: Vec::new(),
+            finished_wave: Vec::new(),
+            activation_order: ActivationOrder::default(),
+            limits: TurnLimits::default(),
+            activation_count: 0,
+            seen_states: HashSet::new(),
+            overload: None,
         }
     }
 
+    /// A runner using the limits and activation order from the config.
+    pub fn configured(game: Game, config: &GameConfig) -> Self {
+        Self::new(game)
+            .with_limits(config.turn.limits())
+            .with_activation_order(config.game.activation_order)
+    }
+
+    pub fn with_limits(mut self, limits: TurnLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn with_activation_order(mut self, activation_order: ActivationOrder) -> Self {
+        self.activation_order = activation_order;
+        self
+    }
+
     fn push_cmd(&mut self, cmd: Cmd) {
         self.commands.push(cmd);
+        if self.commands.len() >= self.limits.max_commands {
+            self.overload(OverloadReason::TooManyCommands);
+        }
     }
 
-    fn push_activated(&mut self, actor_id: ActorId) {
-        self.activated_actors.push(actor_id);
+    /// Only the first reason is kept.
+    fn overload(&mut self, reason: OverloadReason) {
+        self.overload.get_or_insert(reason);
+    }
+
+    /// Hashes what decides what happens next: the board and the activation stack.
+    /// Activation counts are part of it, so a chain that runs out of them on its
+    /// own is never cut short. Actor ids are left out.
+    fn state_hash(&self) -> u64 {
+        let board = self.game.board();
+        let mut actors: Vec<_> = board
+            .actor_ids()
+            .map(|actor_id| {
+                let actor = board.actor_id_to_actor(actor_id).unwrap();
+                (
+                    actor.coord,
+                    actor.looks_to,
+                    &actor.actor_type_id,
+                    actor.activations_left,
+                    actor.activated,
+                )
+            })
+            .collect();
+        actors.sort_by_key(|(coord, ..)| (coord.x, coord.y));
+        let coord_of =
+            |actor_id: &ActorId| board.actor_id_to_actor(actor_id).map(|actor| actor.coord);
+        let mut hasher = DefaultHasher::new();
+        actors.hash(&mut hasher);
+        for actor_id in self.activated_actors.iter().chain(&self.next_wave) {
+            coord_of(actor_id).hash(&mut hasher);
+        }
+        self.next_wave.len().hash(&mut hasher);
+        hasher.finish()
     }
 
     fn pop_activated(&mut self) -> Option<ActorId> {
-        self.activated_actors.pop()
+        match self.activation_order {
+            ActivationOrder::DepthFirst => self.activated_actors.pop_back(),
+            ActivationOrder::BreadthFirst => self.activated_actors.pop_front(),
+            ActivationOrder::Simultaneous => {
+                if self.activated_actors.is_empty() {
+                    self.next_wave_of_actors();
+                }
+                self.activated_actors.pop_front()
+            }
+        }
+    }
+
+    /// Deactivates the wave that just ran, then activates the one it hit.
+    fn next_wave_of_actors(&mut self) {
+        for actor_id in std::mem::take(&mut self.finished_wave) {
+            self.deactivate(actor_id);
+            self.push_cmd(Cmd::Deactivate(actor_id));
+        }
+        let next_wave = std::mem::take(&mut self.next_wave);
+        if self.overload.is_none() {
+            for actor_id in next_wave {
+                self.activate_now(actor_id);
+            }
+        }
     }
 
     fn activate(&mut self, actor_id: ActorId) {
+        if self.activation_order == ActivationOrder::Simultaneous {
+            if !self.next_wave.contains(&actor_id) {
+                self.next_wave.push(actor_id);
+            }
+        } else {
+            self.activate_now(actor_id);
+        }
+    }
+
+    fn activate_now(&mut self, actor_id: ActorId) {
         let view = self.game.actor_view(&actor_id).unwrap();
         if !view.actor.activated && view.actor.activations_left > 0 {
-            self.game.earn_prize_gold(view.actor_type.prize as u64);
-            self.push_cmd(Cmd::Prize(actor_id, view.actor_type.prize as u64));
+            if self.activation_count >= self.limits.max_activations {
+                self.overload(OverloadReason::TooManyActivations);
+                return;
+            }
+            self.activation_count += 1;
+            let prize = if self
+                .game
+                .round_rules()
+                .pays_prizes_on(self.game.turn_of_round())
+            {
+                view.actor_type.prize as u64
+            } else {
+                0
+            };
+            self.game.earn_prize_gold(prize);
+            self.push_cmd(Cmd::Prize(actor_id, prize));
             self.game.update_actor(&actor_id, |actor| {
                 actor.activated = true;
                 actor.activations_left -= 1;
             });
-            self.push_activated(actor_id);
+            self.activated_actors.push_back(actor_id);
             self.push_cmd(Cmd::Activate(actor_id));
         }
     }
@@ -81,9 +255,24 @@ impl Runner {
         self.activate(self.game.board().start_actor_id());
 
         while let Some(actor_id) = self.pop_activated() {
+            if self.overload.is_some() {
+                // dropped without running
+                self.deactivate(actor_id);
+                self.commands.push(Cmd::Deactivate(actor_id));
+                continue;
+            }
             self.run_actor(actor_id);
+            if self.overload.is_none() && !self.seen_states.insert(self.state_hash()) {
+                self.overload(OverloadReason::Loop);
+            }
+        }
+        if let Some(reason) = self.overload {
+            warn!("overload: {reason:?}");
+            self.commands.push(Cmd::Overload(reason));
         }
 
+        self.game.record_chain(self.activation_count as u32);
+
         // return the end_game
         let mut end_game = self.game.clone();
         end_game.set_board(self.saved_board.clone());
@@ -94,18 +283,129 @@ impl Runner {
     }
 
     fn run_actor(&mut self, actor_id: ActorId) {
-        let view = self.game.actor_view(&actor_id).unwrap();
-        let program = &view.actor_type.program;
-        for step in program.iter() {
-            match step {
-                Action::Forward => self.move_actor_forward(actor_id),
-                Action::Push(rel_dir) => self.actor_push(actor_id, *rel_dir),
-                Action::Turn(rel_dir) => self.turn_actor(actor_id, *rel_dir),
-                Action::Hit(ivec2s) => self.process_actor_hits(actor_id, ivec2s),
+        // It may have been destroyed since it was activated.
+        let Some(view) = self.game.actor_view(&actor_id) else {
+            return;
+        };
+        let mut steps_left = MAX_ACTOR_STEPS;
+        if !self.run_actions(actor_id, &view.actor_type.program.0, &mut steps_left)
+            && steps_left == 0
+        {
+            warn!("{actor_id:?} ran out of steps");
+        }
+        if self.game.board().actor_id_to_actor(&actor_id).is_some() {
+            if self.activation_order == ActivationOrder::Simultaneous {
+                self.finished_wave.push(actor_id);
+            } else {
+                self.deactivate(actor_id);
+                self.push_cmd(Cmd::Deactivate(actor_id));
             }
         }
-        self.deactivate(actor_id);
-        self.push_cmd(Cmd::Deactivate(actor_id));
+    }
+
+    /// Returns false when the step budget ran out or the actor destroyed itself,
+    /// which stops the whole program.
+    fn run_actions(&mut self, actor_id: ActorId, actions: &[Action], steps_left: &mut u32) -> bool {
+        for action in actions {
+            if !take_step(steps_left) {
+                return false;
+            }
+            let completed = match action {
+                Action::Forward => {
+                    self.move_actor_forward(actor_id);
+                    true
+                }
+                Action::Push(rel_dir) => {
+                    self.actor_push(actor_id, *rel_dir);
+                    if self.game.round_rules().pushes_cost_activation() {
+                        self.game.update_actor(&actor_id, |actor| {
+                            actor.activations_left = actor.activations_left.saturating_sub(1);
+                        });
+                    }
+                    true
+                }
+                Action::Pull(rel_dir) => {
+                    self.actor_pull(actor_id, *rel_dir);
+                    true
+                }
+                Action::Turn(rel_dir) => {
+                    self.turn_actor(actor_id, *rel_dir);
+                    true
+                }
+                Action::Hit(ivec2s) => {
+                    self.process_actor_hits(actor_id, ivec2s);
+                    true
+                }
+                Action::Spawn {
+                    actor_type,
+                    offset,
+                    persistent,
+                } => {
+                    self.spawn_actor(actor_id, actor_type, *offset, *persistent);
+                    true
+                }
+                Action::Destroy { offset, persistent } => {
+                    self.destroy_actor(actor_id, *offset, *persistent);
+                    self.game.board().actor_id_to_actor(&actor_id).is_some()
+                }
+                Action::IfBlocked {
+                    dir,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if self.is_blocked(actor_id, *dir) {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    self.run_actions(actor_id, branch, steps_left)
+                }
+                Action::IfEmpty {
+                    offset,
+                    then,
+                    otherwise,
+                } => {
+                    let branch = if self.is_empty(actor_id, *offset) {
+                        then
+                    } else {
+                        otherwise
+                    };
+                    self.run_actions(actor_id, branch, steps_left)
+                }
+                Action::Repeat { times, actions } => (0..*times).all(|_| {
+                    self.overload.is_none()
+                        && take_step(steps_left)
+                        && self.run_actions(actor_id, actions, steps_left)
+                }),
+                Action::RepeatUntilBlocked(actions) => loop {
+                    if self.is_blocked(actor_id, RelDir::Front) {
+                        break true;
+                    }
+                    if self.overload.is_some()
+                        || !(take_step(steps_left)
+                            && self.run_actions(actor_id, actions, steps_left))
+                    {
+                        break false;
+                    }
+                },
+            };
+            if !completed || self.overload.is_some() {
+                return false;
+            }
+        }
+        true
+    }
+
+    fn is_blocked(&self, actor_id: ActorId, rel_dir: RelDir) -> bool {
+        let actor = self.game.board().actor_id_to_actor(&actor_id).unwrap();
+        let coord = actor.looks_to.apply_relative(rel_dir).apply_to(actor.coord);
+        self.game.board().coord_to_actor_id(&coord).is_some()
+    }
+
+    fn is_empty(&self, actor_id: ActorId, offset: IVec2) -> bool {
+        let actor = self.game.board().actor_id_to_actor(&actor_id).unwrap();
+        let coord = actor.looks_to.rel_coord_to_coord(actor.coord, offset);
+        self.game.board().coord_to_actor_id(&coord).is_none()
     }
 
     fn move_actor_forward(&mut self, actor_id: ActorId) {
@@ -218,6 +518,132 @@ impl Runner {
         }
     }
 
+    /// The puller steps back, away from `rel_dir`, and the line of pushable actors
+    /// adjacent in that direction follows it by one tile. The line ends at the first
+    /// empty tile or unpushable actor, which stays in place. Nothing moves if the
+    /// tile behind the puller is taken.
+    fn actor_pull(&mut self, actor_id: ActorId, rel_dir: RelDir) {
+        let view = self.game.actor_view(&actor_id).unwrap();
+        let pull_dir = view.actor.looks_to.apply_relative(rel_dir);
+        let back_dir = pull_dir.apply_relative(RelDir::Back);
+
+        let coord = view.actor.coord;
+        let puller_coord = back_dir.apply_to(coord);
+        let can_pull = self.game.board().coord_to_actor_id(&puller_coord).is_none();
+
+        let mut pulled_actors = vec![];
+        for (coord, actor_id) in self.actor_line(pull_dir.apply_to(coord), pull_dir) {
+            match actor_id {
+                Some(actor_id) if self.game.actor_view(&actor_id).unwrap().actor_type.pushable => {
+                    pulled_actors.push((actor_id, back_dir.apply_to(coord)));
+                }
+                _ => break,
+            }
+        }
+
+        // try pull
+        self.push_cmd(Cmd::TryPush(Dest {
+            from_actor_id: actor_id,
+            to_coord: puller_coord,
+        }));
+        for (actor_id, to_coord) in &pulled_actors {
+            self.push_cmd(Cmd::TryPush(Dest {
+                from_actor_id: *actor_id,
+                to_coord: *to_coord,
+            }));
+        }
+        if can_pull {
+            // the puller frees the first tile, each pulled actor frees the next one
+            self.game.update_actor(&actor_id, |actor| {
+                actor.coord = puller_coord;
+            });
+            self.push_cmd(Cmd::CompletePush(Dest {
+                from_actor_id: actor_id,
+                to_coord: puller_coord,
+            }));
+            for (actor_id, to_coord) in &pulled_actors {
+                self.activate(*actor_id);
+                self.game.update_actor(actor_id, |actor| {
+                    actor.coord = *to_coord;
+                });
+                self.push_cmd(Cmd::CompletePush(Dest {
+                    from_actor_id: *actor_id,
+                    to_coord: *to_coord,
+                }));
+            }
+        } else {
+            // cancel pulls
+            for (actor_id, to_coord) in pulled_actors.iter().rev() {
+                self.push_cmd(Cmd::CancelPush(Dest {
+                    from_actor_id: *actor_id,
+                    to_coord: *to_coord,
+                }));
+            }
+            // still activate first though
+            if let Some((actor_id, _to_coord)) = pulled_actors.first() {
+                self.activate(*actor_id);
+            }
+            self.push_cmd(Cmd::CancelPush(Dest {
+                from_actor_id: actor_id,
+                to_coord: puller_coord,
+            }));
+        }
+    }
+
+    fn spawn_actor(
+        &mut self,
+        actor_id: ActorId,
+        actor_type_id: &ActorTypeId,
+        offset: IVec2,
+        persistent: bool,
+    ) {
+        let actor = self.game.board().actor_id_to_actor(&actor_id).unwrap();
+        let looks_to = actor.looks_to;
+        let coord = looks_to.rel_coord_to_coord(actor.coord, offset);
+        let Some(spawned_id) = self.game.new_actor(actor_type_id, coord) else {
+            return;
+        };
+        self.game
+            .update_actor(&spawned_id, |spawned| spawned.looks_to = looks_to);
+        let spawned = self
+            .game
+            .board()
+            .actor_id_to_actor(&spawned_id)
+            .unwrap()
+            .clone();
+        if persistent
+            && self
+                .saved_board
+                .insert_actor(spawned_id, spawned.clone())
+                .is_err()
+        {
+            warn!("{coord} was taken when the turn started, the spawn won't last");
+        }
+        self.push_cmd(Cmd::Spawn(spawned_id, spawned));
+    }
+
+    fn destroy_actor(&mut self, actor_id: ActorId, offset: IVec2, persistent: bool) {
+        let actor = self.game.board().actor_id_to_actor(&actor_id).unwrap();
+        let coord = actor.looks_to.rel_coord_to_coord(actor.coord, offset);
+        let Some(destroyed_id) = self.game.board().coord_to_actor_id(&coord) else {
+            return;
+        };
+        if destroyed_id == self.game.board().start_actor_id() {
+            return;
+        }
+        let destroyed = self.game.remove_actor(&destroyed_id).unwrap();
+        if destroyed.activated {
+            self.activated_actors.retain(|id| *id != destroyed_id);
+            self.finished_wave.retain(|id| *id != destroyed_id);
+            self.push_cmd(Cmd::Deactivate(destroyed_id));
+        }
+        self.next_wave.retain(|id| *id != destroyed_id);
+        if persistent {
+            self.saved_board.remove_actor(&destroyed_id);
+        }
+        self.push_cmd(Cmd::Despawn(destroyed_id));
+    }
+
     fn turn_actor(&mut self, actor_id: ActorId, rel_dir: RelDir) {
         self.game.update_actor(&actor_id, |actor| {
             actor.looks_to = actor.looks_to.apply_relative(rel_dir);
@@ -240,3 +666,590 @@ impl Runner {
         }
     }
 }
+
+fn take_step(steps_left: &mut u32) -> bool {
+    if *steps_left == 0 {
+        return false;
+    }
+    *steps_left -= 1;
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::ivec2;
+
+    use super::*;
+    use crate::model::{
+        actor_type::ActorTypeId,
+        actor_types::ActorTypes,
+        rounds::{RoundModifier, RoundSchedule, ScheduledRound},
+        test_utils,
+    };
+
+    /// A start actor that pokes the tile to its right, plus a `tested` type running `program`.
+    fn game_with(program: &str) -> Game {
+        let actor_types: ActorTypes = toml::from_str(&format!(
+            r#"
+            [start]
+            name = "Start"
+            max_activations = 1
+            prize = 1
+            cost = 0
+            sprite_name = "start.png"
+            program = "hit (1, 0)"
+            description = ""
+
+            [tested]
+            name = "Tested"
+            max_activations = 1
+            prize = 0
+            cost = 1
+            sprite_name = "tested.png"
+            program = "{program}"
+            description = ""
+
+            [rock]
+            name = "Rock"
+            max_activations = 1
+            prize = 0
+            cost = 1
+            sprite_name = "rock.png"
+            program = []
+            description = ""
+            "#
+        ))
+        .unwrap();
+        Game::with_seed(&test_utils::game_config().game, actor_types, 0)
+    }
+
+    fn place(game: &mut Game, actor_type: &str, coord: IVec2) -> ActorId {
+        game.new_actor(&ActorTypeId::new(actor_type.to_string()), coord)
+            .unwrap()
+    }
+
+    fn coord_after_turn(game: Game, actor_id: ActorId) -> (IVec2, Vec<Cmd>) {
+        let mut runner = Runner::new(game);
+        let (_end_game, cmds) = runner.run();
+        let coord = runner
+            .game
+            .board()
+            .actor_id_to_actor(&actor_id)
+            .unwrap()
+            .coord;
+        (coord, cmds)
+    }
+
+    #[test]
+    fn repeat_until_blocked_stops_in_front_of_an_actor() {
+        let mut game = game_with("repeat until blocked { forward }");
+        let tested = place(&mut game, "tested", ivec2(1, 0));
+        place(&mut game, "rock", ivec2(5, 0));
+        assert_eq!(coord_after_turn(game, tested).0, ivec2(4, 0));
+    }
+
+    #[test]
+    fn if_blocked_picks_a_branch() {
+        let program = "if blocked left { forward } else { turn right; forward }";
+        let mut game = game_with(program);
+        let tested = place(&mut game, "tested", ivec2(1, 0));
+        assert_eq!(coord_after_turn(game, tested).0, ivec2(1, -1));
+
+        let mut game = game_with(program);
+        let tested = place(&mut game, "tested", ivec2(1, 0));
+        place(&mut game, "rock", ivec2(1, 1));
+        assert_eq!(coord_after_turn(game, tested).0, ivec2(2, 0));
+    }
+
+    #[test]
+    fn endless_loops_run_out_of_steps() {
+        // keeps facing right, away from the start actor
+        let mut game = game_with("repeat until blocked { turn left; turn right }");
+        let tested = place(&mut game, "tested", ivec2(1, 0));
+        let (_coord, cmds) = coord_after_turn(game, tested);
+        let turns = cmds
+            .iter()
+            .filter(|cmd| matches!(cmd, Cmd::Turn(..)))
+            .count() as u32;
+        // one step for the loop action, then three per loop turn: its own and two turns
+        assert_eq!(turns, (MAX_ACTOR_STEPS - 1) / 3 * 2);
+        assert!(matches!(cmds.last(), Some(Cmd::Done)));
+    }
+
+    #[test]
+    fn limits_end_the_turn_with_an_overload() {
+        let overload = |limits| {
+            let mut game = game_with("forward");
+            place(&mut game, "tested", ivec2(1, 0));
+            let (_end_game, cmds) = Runner::new(game).with_limits(limits).run();
+            assert!(matches!(cmds.last(), Some(Cmd::Done)));
+            cmds.iter().find_map(|cmd| match cmd {
+                Cmd::Overload(reason) => Some(*reason),
+                _ => None,
+            })
+        };
+        assert_eq!(overload(TurnLimits::default()), None);
+        assert_eq!(
+            overload(TurnLimits {
+                max_activations: 1,
+                ..Default::default()
+            }),
+            Some(OverloadReason::TooManyActivations)
+        );
+        assert_eq!(
+            overload(TurnLimits {
+                max_commands: 2,
+                ..Default::default()
+            }),
+            Some(OverloadReason::TooManyCommands)
+        );
+    }
+
+    #[test]
+    fn ping_pong_ends_when_activations_run_out() {
+        // two actors hitting each other back and forth
+        let actor_types: ActorTypes = toml::from_str(
+            r#"
+            [start]
+            name = "Start"
+            max_activations = 1
+            prize = 1
+            cost = 0
+            sprite_name = "start.png"
+            program = "hit (1, 0)"
+            description = ""
+
+            [pong]
+            name = "Pong"
+            max_activations = 5
+            prize = 1
+            cost = 1
+            sprite_name = "pong.png"
+            program = "hit (1, 0), (-1, 0)"
+            description = ""
+            "#,
+        )
+        .unwrap();
+        let mut game = Game::with_seed(&test_utils::game_config().game, actor_types, 0);
+        place(&mut game, "pong", ivec2(1, 0));
+        place(&mut game, "pong", ivec2(2, 0));
+        let (_end_game, cmds) = Runner::new(game).run();
+        let activations = cmds
+            .iter()
+            .filter(|cmd| matches!(cmd, Cmd::Activate(..)))
+            .count();
+        // the start, then both pongs until they are spent
+        assert_eq!(activations, 11);
+        assert!(!cmds.iter().any(|cmd| matches!(cmd, Cmd::Overload(_))));
+        assert!(matches!(cmds.last(), Some(Cmd::Done)));
+    }
+
+    /// Puts the game in a round that has `modifier`.
+    fn with_modifier(game: &mut Game, modifier: RoundModifier) {
+        game.start_round(&RoundSchedule {
+            rounds: vec![ScheduledRound {
+                round: game.round(),
+                modifiers: vec![modifier],
+            }],
+            ..Default::default()
+        });
+    }
+
+    fn prizes(cmds: &[Cmd]) -> Vec<u64> {
+        cmds.iter()
+            .filter_map(|cmd| match cmd {
+                Cmd::Prize(_, amount) => Some(*amount),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn prizes_on_even_turns_only() {
+        let mut game = game_with("");
+        with_modifier(&mut game, RoundModifier::PrizesOnEvenTurns);
+        let (game, cmds) = Runner::new(game).run();
+        assert_eq!(prizes(&cmds), vec![0]);
+        assert_eq!(game.gold_this_turn(), 0);
+        let (game, cmds) = Runner::new(game).run();
+        assert_eq!(prizes(&cmds), vec![1]);
+        assert_eq!(game.gold_this_turn(), 1);
+    }
+
+    #[test]
+    fn pushes_cost_an_activation() {
+        let run = |modifier: Option<RoundModifier>| {
+            let mut game = game_with("push front; push front");
+            if let Some(modifier) = modifier {
+                with_modifier(&mut game, modifier);
+            }
+            let tested = place(&mut game, "tested", ivec2(1, 0));
+            game.update_actor(&tested, |actor| actor.activations_left = 3);
+            place(&mut game, "rock", ivec2(2, 0));
+            let mut runner = Runner::new(game);
+            let (_end_game, cmds) = runner.run();
+            let pushes = cmds
+                .iter()
+                .filter(|cmd| matches!(cmd, Cmd::CompletePush(..)))
+                .count();
+            let actor = runner.game.board().actor_id_to_actor(&tested).unwrap();
+            (actor.activations_left, pushes)
+        };
+        let (normal, normal_pushes) = run(None);
+        let (costly, costly_pushes) = run(Some(RoundModifier::PushesCostActivation));
+        assert_eq!((normal, costly), (2, 0));
+        // the pushes themselves go through the same
+        assert!(normal_pushes > 0);
+        assert_eq!(normal_pushes, costly_pushes);
+    }
+
+    /// The commands of a turn with the shipped actor types.
+    fn cmd_stream(activation_order: ActivationOrder, actors: &[(&str, IVec2, Dir)]) -> Vec<String> {
+        let mut game = Game::with_seed(
+            &test_utils::game_config().game,
+            test_utils::actor_types(),
+            0,
+        );
+        for (actor_type, coord, looks_to) in actors {
+            let actor_id = place(&mut game, actor_type, *coord);
+            game.update_actor(&actor_id, |actor| actor.looks_to = *looks_to);
+        }
+        let (_end_game, cmds) = Runner::new(game)
+            .with_activation_order(activation_order)
+            .run();
+        cmds.iter().map(|cmd| format!("{cmd:?}")).collect()
+    }
+
+    /// A good boy hitting two hammer times, each hitting one more.
+    const CHAIN: &[(&str, IVec2, Dir)] = &[
+        ("good_boy", ivec2(1, 0), Dir::Right),
+        ("hammer_time", ivec2(1, 1), Dir::Right),
+        ("hammer_time", ivec2(1, -1), Dir::Right),
+        ("hammer_time", ivec2(2, 1), Dir::Right),
+        ("hammer_time", ivec2(2, -1), Dir::Right),
+    ];
+
+    /// A desperado between two hammer times that hit it back, so that both of them
+    /// hit it again during the same wave.
+    const CROSSFIRE: &[(&str, IVec2, Dir)] = &[
+        ("hammer_time", ivec2(1, 0), Dir::Right),
+        ("desperado", ivec2(2, 0), Dir::Right),
+        ("hammer_time", ivec2(3, 0), Dir::Left),
+    ];
+
+    #[test]
+    fn chain_cmd_streams() {
+        assert_eq!(
+            cmd_stream(ActivationOrder::DepthFirst, CHAIN),
+            [
+                "Prize(ActorId(1), 1)",
+                "Activate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(1), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 3)",
+                "Activate(ActorId(2))",
+                "Deactivate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(1, 1) })",
+                "Prize(ActorId(3), 1)",
+                "Activate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(1, -1) })",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "MoveTo(Ok(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) }))",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, -1) })",
+                "Prize(ActorId(6), 1)",
+                "Activate(ActorId(6))",
+                "Deactivate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(6), to_coord: IVec2(3, -1) })",
+                "Deactivate(ActorId(6))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(2, 1) })",
+                "Prize(ActorId(5), 1)",
+                "Activate(ActorId(5))",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(5), to_coord: IVec2(3, 1) })",
+                "Deactivate(ActorId(5))",
+                "Done",
+            ]
+        );
+        assert_eq!(
+            cmd_stream(ActivationOrder::BreadthFirst, CHAIN),
+            [
+                "Prize(ActorId(1), 1)",
+                "Activate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(1), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 3)",
+                "Activate(ActorId(2))",
+                "Deactivate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(1, 1) })",
+                "Prize(ActorId(3), 1)",
+                "Activate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(1, -1) })",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "MoveTo(Ok(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) }))",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(2, 1) })",
+                "Prize(ActorId(5), 1)",
+                "Activate(ActorId(5))",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, -1) })",
+                "Prize(ActorId(6), 1)",
+                "Activate(ActorId(6))",
+                "Deactivate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(5), to_coord: IVec2(3, 1) })",
+                "Deactivate(ActorId(5))",
+                "Hit(Dest { from_actor_id: ActorId(6), to_coord: IVec2(3, -1) })",
+                "Deactivate(ActorId(6))",
+                "Done",
+            ]
+        );
+        assert_eq!(
+            cmd_stream(ActivationOrder::Simultaneous, CHAIN),
+            [
+                "Prize(ActorId(1), 1)",
+                "Activate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(1), to_coord: IVec2(1, 0) })",
+                "Deactivate(ActorId(1))",
+                "Prize(ActorId(2), 3)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(1, 1) })",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(1, -1) })",
+                "MoveTo(Ok(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) }))",
+                "Deactivate(ActorId(2))",
+                "Prize(ActorId(3), 1)",
+                "Activate(ActorId(3))",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(2, 1) })",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, -1) })",
+                "Deactivate(ActorId(3))",
+                "Deactivate(ActorId(4))",
+                "Prize(ActorId(5), 1)",
+                "Activate(ActorId(5))",
+                "Prize(ActorId(6), 1)",
+                "Activate(ActorId(6))",
+                "Hit(Dest { from_actor_id: ActorId(5), to_coord: IVec2(3, 1) })",
+                "Hit(Dest { from_actor_id: ActorId(6), to_coord: IVec2(3, -1) })",
+                "Deactivate(ActorId(5))",
+                "Deactivate(ActorId(6))",
+                "Done",
+            ]
+        );
+    }
+
+    #[test]
+    fn crossfire_cmd_streams() {
+        // Every order bounces between the same actors until their activations run out.
+        assert_eq!(
+            cmd_stream(ActivationOrder::DepthFirst, CROSSFIRE),
+            [
+                "Prize(ActorId(1), 1)",
+                "Activate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(1), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Deactivate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(4))",
+                "Done",
+            ]
+        );
+        assert_eq!(
+            cmd_stream(ActivationOrder::BreadthFirst, CROSSFIRE),
+            [
+                "Prize(ActorId(1), 1)",
+                "Activate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(1), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Deactivate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, 0) })",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Deactivate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, 0) })",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Deactivate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(4))",
+                "Done",
+            ]
+        );
+        // Both hammer times of the fourth wave hit the desperado, which is activated once.
+        assert_eq!(
+            cmd_stream(ActivationOrder::Simultaneous, CROSSFIRE),
+            [
+                "Prize(ActorId(1), 1)",
+                "Activate(ActorId(1))",
+                "Hit(Dest { from_actor_id: ActorId(1), to_coord: IVec2(1, 0) })",
+                "Deactivate(ActorId(1))",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(2))",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(4))",
+                "Deactivate(ActorId(2))",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Prize(ActorId(2), 1)",
+                "Activate(ActorId(2))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(2), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(4))",
+                "Deactivate(ActorId(2))",
+                "Prize(ActorId(3), 3)",
+                "Activate(ActorId(3))",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(3, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(1, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(4, 0) })",
+                "Hit(Dest { from_actor_id: ActorId(3), to_coord: IVec2(0, 0) })",
+                "Deactivate(ActorId(3))",
+                "Prize(ActorId(4), 1)",
+                "Activate(ActorId(4))",
+                "Hit(Dest { from_actor_id: ActorId(4), to_coord: IVec2(2, 0) })",
+                "Deactivate(ActorId(4))",
+                "Done",
+            ]
+        );
+    }
+
+    #[test]
+    fn pull_drags_the_adjacent_line() {
+        let mut game = game_with("pull right");
+        let tested = place(&mut game, "tested", ivec2(1, 0));
+        let near = place(&mut game, "rock", ivec2(1, -1));
+        let far = place(&mut game, "rock", ivec2(1, -2));
+        let mut runner = Runner::new(game);
+        runner.run();
+        let board = runner.game.board();
+        let actor = |actor_id| board.actor_id_to_actor(&actor_id).unwrap();
+        assert_eq!(actor(tested).coord, ivec2(1, 1));
+        assert_eq!(actor(near).coord, ivec2(1, 0));
+        assert_eq!(actor(far).coord, ivec2(1, -1));
+        assert_eq!(actor(near).activations_left, 0);
+        assert_eq!(actor(far).activations_left, 0);
+    }
+
+    #[test]
+    fn pull_is_cancelled_when_the_way_back_is_taken() {
+        let mut game = game_with("pull right");
+        let tested = place(&mut game, "tested", ivec2(1, 0));
+        let near = place(&mut game, "rock", ivec2(1, -1));
+        place(&mut game, "rock", ivec2(1, 1));
+        let mut runner = Runner::new(game);
+        let (_end_game, cmds) = runner.run();
+        let board = runner.game.board();
+        let actor = |actor_id| board.actor_id_to_actor(&actor_id).unwrap();
+        assert_eq!(actor(tested).coord, ivec2(1, 0));
+        assert_eq!(actor(near).coord, ivec2(1, -1));
+        assert!(cmds.iter().any(|cmd| matches!(cmd, Cmd::CancelPush(..))));
+        assert!(!cmds.iter().any(|cmd| matches!(cmd, Cmd::CompletePush(..))));
+    }
+
+    #[test]
+    fn board_changes_only_last_when_persistent() {
+        let mut game = game_with("spawn rock (1, 0); destroy (0, 1) persistent; destroy (0, 0)");
+        let tested = place(&mut game, "tested", ivec2(1, 0));
+        let destroyed = place(&mut game, "rock", ivec2(1, 1));
+        let (end_game, cmds) = Runner::new(game).run();
+        let end_board = end_game.board();
+        // the temporary spawn and self destruction are undone, the persistent destruction is not
+        assert!(end_board.coord_to_actor_id(&ivec2(2, 0)).is_none());
+        assert!(end_board.actor_id_to_actor(&destroyed).is_none());
+        assert!(end_board.actor_id_to_actor(&tested).is_some());
+        assert!(end_board.check_consistency().is_ok());
+        assert!(cmds.iter().any(|cmd| matches!(cmd, Cmd::Spawn(..))));
+        let despawns = cmds
+            .iter()
+            .filter(|cmd| matches!(cmd, Cmd::Despawn(..)))
+            .count();
+        assert_eq!(despawns, 2);
+    }
+}
@@ -0,0 +1,131 @@
+//! Versioned RON save format for an in-progress run.
+
+use thiserror::Error;
+
+use crate::model::{actor_types::ActorTypes, game::Game};
+
+/// Bump this whenever the saved shape of [`Game`] changes, and add a matching
+/// arm to [`SaveFile::from_ron`] that reads the older shape.
+pub const SAVE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SaveFile {
+    pub version: u32,
+    pub game: Game,
+}
+
+/// Only the version is read first, so that any version can be dispatched on.
+#[derive(serde::Deserialize)]
+struct SaveHeader {
+    version: u32,
+}
+
+#[derive(Error, Debug)]
+pub enum SaveError {
+    #[error("could not parse save: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not write save: {0}")]
+    Write(#[from] ron::Error),
+    #[error("unsupported save version {0} (current is {SAVE_VERSION})")]
+    UnsupportedVersion(u32),
+}
+
+impl SaveFile {
+    pub fn new(game: &Game) -> Self {
+        Self {
+            version: SAVE_VERSION,
+            game: game.clone(),
+        }
+    }
+
+    pub fn to_ron(&self) -> Result<String, SaveError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    /// Parses a save, migrating it from older versions when needed.
+    pub fn from_ron(text: &str) -> Result<Self, SaveError> {
+        let header: SaveHeader = ron::from_str(text)?;
+        match header.version {
+            SAVE_VERSION => Ok(ron::from_str(text)?),
+            // Older versions go here: parse their own shape, then convert it.
+            version => Err(SaveError::UnsupportedVersion(version)),
+        }
+    }
+
+    /// Turns the save back into a game, against the actor types currently loaded.
+    pub fn into_game(self, actor_types: ActorTypes) -> Game {
+        let mut game = self.game;
+        game.attach_actor_types(actor_types);
+        game
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use super::*;
+    use crate::model::{actor_type::ActorTypeId, game::Game, rounds::RoundSchedule, test_utils};
+
+    #[test]
+    fn round_trip() {
+        let config = test_utils::game_config();
+        let actor_types = test_utils::actor_types();
+        let mut game = Game::new(&config.game, actor_types.clone());
+        game.set_gold(100);
+        game.next_round(&RoundSchedule::default());
+        game.new_turn();
+        game.restock();
+        game.restock();
+        game.toggle_shop_lock(1);
+        game.new_actor(&ActorTypeId::new("pusher".to_string()), IVec2::new(1, 0))
+            .unwrap();
+
+        let text = SaveFile::new(&game).to_ron().unwrap();
+        let mut restored = SaveFile::from_ron(&text).unwrap().into_game(actor_types);
+        assert_eq!(restored.gold(), game.gold());
+        assert_eq!(restored.round(), game.round());
+        assert_eq!(restored.turns_left(), game.turns_left());
+        assert_eq!(restored.seed(), game.seed());
+        let (shop, restored_shop) = (game.shop(), restored.shop());
+        assert_eq!(
+            restored_shop.stock().collect::<Vec<_>>(),
+            shop.stock().collect::<Vec<_>>()
+        );
+        for index in 0..shop.stock().count() {
+            assert_eq!(restored_shop.is_locked(index), shop.is_locked(index));
+        }
+        assert_eq!(restored_shop.restock_cost(), shop.restock_cost());
+
+        // the rng picks up where it was
+        game.restock();
+        restored.restock();
+        assert!(restored.shop().stock().eq(game.shop().stock()));
+        assert_eq!(
+            restored.board().actor_ids().count(),
+            game.board().actor_ids().count()
+        );
+        for actor_id in game.board().actor_ids() {
+            let actor = game.board().actor_id_to_actor(actor_id).unwrap();
+            let restored_actor = restored.board().actor_id_to_actor(actor_id).unwrap();
+            assert_eq!(restored_actor.coord, actor.coord);
+            assert_eq!(restored_actor.actor_type_id, actor.actor_type_id);
+            assert_eq!(
+                restored.board().coord_to_actor_id(&actor.coord),
+                Some(*actor_id)
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_version() {
+        let text = "(version: 999, game: ())";
+        assert!(matches!(
+            SaveFile::from_ron(text),
+            Err(SaveError::UnsupportedVersion(999))
+        ));
+    }
+}
@@ -1,91 +1,448 @@
+use rand::Rng;
+
 use crate::model::{
-    actor_type::{ActorType, ActorTypeId},
+    actor_type::{ActorType, ActorTypeId, Rarity},
     actor_types::ActorTypes,
 };
 
-const RESTOCK_ITEM_COUNT: usize = 3;
+/// The odds of each rarity showing up in the shop, from `round` on.
+#[derive(Debug, Clone, Copy, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct RarityWeights {
+    pub round: u32,
+    pub common: u32,
+    pub rare: u32,
+    pub epic: u32,
+}
+
+impl Default for RarityWeights {
+    fn default() -> Self {
+        Self {
+            round: 1,
+            common: 1,
+            rare: 1,
+            epic: 1,
+        }
+    }
+}
+
+impl RarityWeights {
+    pub fn weight(&self, rarity: Rarity) -> u32 {
+        match rarity {
+            Rarity::Common => self.common,
+            Rarity::Rare => self.rare,
+            Rarity::Epic => self.epic,
+        }
+    }
+
+    /// The last entry whose round has been reached, all rarities alike if there is none.
+    pub fn for_round(weights: &[RarityWeights], round: u32) -> RarityWeights {
+        weights
+            .iter()
+            .filter(|weights| weights.round <= round)
+            .max_by_key(|weights| weights.round)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+/// How the shop behaves over a run, taken from the game config.
+/// The default matches runs saved before it was configurable.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ShopRules {
+    pub restock_cost: f32,
+    pub restock_multiplier: f32,
+    pub slots: usize,
+    pub max_slots: usize,
+    /// One more slot every that many rounds, never if 0.
+    pub rounds_per_extra_slot: u32,
+    pub free_restocks_per_round: u32,
+    /// Puts the restock cost back to `restock_cost` at each new round.
+    pub reset_restock_cost: bool,
+    pub rarity_weights: Vec<RarityWeights>,
+}
+
+impl Default for ShopRules {
+    fn default() -> Self {
+        Self {
+            restock_cost: 1.0,
+            restock_multiplier: 1.5,
+            slots: 3,
+            max_slots: 3,
+            rounds_per_extra_slot: 0,
+            free_restocks_per_round: 1,
+            reset_restock_cost: false,
+            rarity_weights: Vec::new(),
+        }
+    }
+}
+
+impl ShopRules {
+    pub fn slots(&self, round: u32) -> usize {
+        let extra = match self.rounds_per_extra_slot {
+            0 => 0,
+            rounds => (round.saturating_sub(1) / rounds) as usize,
+        };
+        (self.slots + extra).min(self.max_slots.max(self.slots))
+    }
+}
+
+/// Where a bought item was in the shop, so that it can be put back.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ShopSlot {
+    pub index: usize,
+    pub locked: bool,
+}
 
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, serde::Serialize, serde::Deserialize)]
 pub struct Shop {
     restock_cost: f32,
-    restock_multiplier: f32,
+    #[serde(default)]
+    rules: ShopRules,
+    #[serde(default)]
+    free_restocks_left: u32,
     stock: Vec<ActorTypeId>,
+    /// One per stock slot. Locked slots are kept by restocks.
+    #[serde(default)]
+    locked: Vec<bool>,
+    /// Set by a round modifier, nothing can be restocked or bought.
+    #[serde(default)]
+    closed: bool,
 }
 
 impl Shop {
-    pub(super) fn new(restock_multiplier: f32) -> Self {
+    pub(super) fn new(rules: ShopRules) -> Self {
         Self {
-            restock_cost: 0.0,
-            restock_multiplier,
+            restock_cost: rules.restock_cost,
+            free_restocks_left: rules.free_restocks_per_round,
+            rules,
             stock: Vec::new(),
+            locked: Vec::new(),
+            closed: false,
+        }
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.closed
+    }
+
+    pub(super) fn set_closed(&mut self, closed: bool) {
+        self.closed = closed;
+    }
+
+    pub fn rules(&self) -> &ShopRules {
+        &self.rules
+    }
+
+    /// Gives back the free restocks, and resets the cost if the rules say so.
+    pub(super) fn new_round(&mut self) {
+        self.free_restocks_left = self.rules.free_restocks_per_round;
+        if self.rules.reset_restock_cost {
+            self.restock_cost = self.rules.restock_cost;
         }
     }
 
+    pub fn free_restocks_left(&self) -> u32 {
+        self.free_restocks_left
+    }
+
     pub fn stock(&self) -> impl Iterator<Item = &ActorTypeId> {
         self.stock.iter()
     }
 
+    pub(super) fn retain_stock(&mut self, mut f: impl FnMut(&ActorTypeId) -> bool) {
+        let slots = self.slots();
+        (self.stock, self.locked) = slots
+            .into_iter()
+            .filter(|(actor_type_id, _)| f(actor_type_id))
+            .unzip();
+    }
+
+    pub fn is_locked(&self, index: usize) -> bool {
+        self.locked.get(index).copied().unwrap_or(false)
+    }
+
+    pub(super) fn toggle_lock(&mut self, index: usize) {
+        if index < self.stock.len() {
+            self.locked.resize(self.stock.len(), false);
+            self.locked[index] = !self.locked[index];
+        }
+    }
+
+    /// The stock along with the lock of each slot.
+    fn slots(&self) -> Vec<(ActorTypeId, bool)> {
+        self.stock
+            .iter()
+            .enumerate()
+            .map(|(index, actor_type_id)| (actor_type_id.clone(), self.is_locked(index)))
+            .collect()
+    }
+
+    /// What the next restock costs, 0 while there are free restocks left.
     pub fn restock_cost(&self) -> u64 {
-        self.restock_cost as u64
+        if self.free_restocks_left > 0 {
+            0
+        } else {
+            self.restock_cost as u64
+        }
     }
 
-    pub(super) fn restock(&mut self, actor_types: &ActorTypes, game_gold: &mut u64) {
-        if *game_gold < self.restock_cost as u64 {
+    pub(super) fn restock(
+        &mut self,
+        actor_types: &ActorTypes,
+        game_gold: &mut u64,
+        round: u32,
+        rng: &mut impl Rng,
+    ) {
+        if self.closed || *game_gold < self.restock_cost() {
             return;
         }
 
-        let mut valid_actors: Vec<(&ActorTypeId, &ActorType)> = actor_types
+        let old_slots = self.slots();
+        let slot_count = self.rules.slots(round);
+        let weights = RarityWeights::for_round(&self.rules.rarity_weights, round);
+        // Sorted, so the picks only depend on the rng and not on hash map order.
+        let mut valid_actors: Vec<(&ActorTypeId, u32)> = actor_types
             .iter()
             .filter(|(_name, actor_type)| {
-                (actor_type.cost > 0) && (actor_type.cost as u64 <= *game_gold)
+                (actor_type.cost > 0)
+                    && (actor_type.cost as u64 <= *game_gold)
+                    && actor_type
+                        .min_round
+                        .is_none_or(|min_round| min_round <= round)
             })
+            .map(|(actor_type_id, actor_type)| (actor_type_id, weights.weight(actor_type.rarity)))
+            .filter(|(_, weight)| *weight > 0)
             .collect();
+        valid_actors.sort_by_key(|(actor_type_id, _)| *actor_type_id);
+        // Locked types are kept in their slot, and not offered again next to it.
+        valid_actors
+            .retain(|(actor_type_id, _)| !old_slots.contains(&((*actor_type_id).clone(), true)));
 
         self.stock.clear();
+        self.locked.clear();
 
-        for _i in 0..RESTOCK_ITEM_COUNT {
-            if valid_actors.is_empty() {
-                break;
+        for slot in 0..slot_count.max(old_slots.len()) {
+            if let Some((actor_type_id, true)) = old_slots.get(slot) {
+                self.stock.push(actor_type_id.clone());
+                self.locked.push(true);
+            } else if slot < slot_count {
+                if let Some(index) = weighted_pick(&valid_actors, rng) {
+                    let (actor_type_id, _weight) = valid_actors.remove(index);
+                    self.stock.push(actor_type_id.clone());
+                    self.locked.push(false);
+                }
             }
-
-            let index = rand::random::<usize>() % valid_actors.len();
-            let (actor_type_id, _actor_type) = valid_actors.remove(index);
-            self.stock.push(actor_type_id.clone());
         }
 
-        // first restock is free
-        if self.restock_cost == 0.0 {
-            self.restock_cost = 1.0
+        if self.free_restocks_left > 0 {
+            self.free_restocks_left -= 1;
         } else {
             *game_gold -= self.restock_cost as u64;
-            self.restock_cost *= self.restock_multiplier
+            self.restock_cost *= self.rules.restock_multiplier
         }
     }
 
+    /// Puts a bought item back in the slot it came from, lock included.
     pub(super) fn return_item(
         &mut self,
         actor_type_id: &ActorTypeId,
         actor_type: &ActorType,
-        index: usize,
+        slot: ShopSlot,
         game_gold: &mut u64,
     ) {
         *game_gold += actor_type.cost as u64;
+        self.locked.resize(self.stock.len(), false);
+        let index = slot.index.min(self.stock.len());
         self.stock.insert(index, actor_type_id.clone());
+        self.locked.insert(index, slot.locked);
     }
 
+    /// Buys the item in the slot at `index`, if it holds that actor type.
     pub(super) fn buy_item(
         &mut self,
+        index: usize,
         actor_type_id: &ActorTypeId,
         actor_type: &ActorType,
         game_gold: &mut u64,
-    ) -> bool {
-        if let Some(index) = self.stock.iter().position(|ati| ati == actor_type_id) {
-            if *game_gold >= actor_type.cost as u64 {
-                *game_gold -= actor_type.cost as u64;
-                self.stock.remove(index);
-                return true;
+    ) -> Option<ShopSlot> {
+        if self.closed
+            || self.stock.get(index) != Some(actor_type_id)
+            || *game_gold < actor_type.cost as u64
+        {
+            return None;
+        }
+        *game_gold -= actor_type.cost as u64;
+        let locked = self.is_locked(index);
+        self.stock.remove(index);
+        if index < self.locked.len() {
+            self.locked.remove(index);
+        }
+        Some(ShopSlot { index, locked })
+    }
+}
+
+/// Picks an index, each item being as likely as its weight.
+fn weighted_pick<T>(items: &[(T, u32)], rng: &mut impl Rng) -> Option<usize> {
+    let total: u32 = items.iter().map(|(_, weight)| weight).sum();
+    if total == 0 {
+        return None;
+    }
+    let mut roll = rng.gen_range(0..total);
+    items.iter().position(|(_, weight)| {
+        if roll < *weight {
+            true
+        } else {
+            roll -= weight;
+            false
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{rng::GameRng, test_utils};
+
+    #[test]
+    fn weighted_picks_follow_rarity_weights() {
+        let weights = RarityWeights {
+            round: 1,
+            common: 6,
+            rare: 3,
+            epic: 1,
+        };
+        let items = [
+            (Rarity::Common, weights.weight(Rarity::Common)),
+            (Rarity::Rare, weights.weight(Rarity::Rare)),
+            (Rarity::Epic, weights.weight(Rarity::Epic)),
+        ];
+        let mut rng = GameRng::new(42);
+        let mut counts = [0; 3];
+        let draws = 10_000;
+        for _ in 0..draws {
+            counts[weighted_pick(&items, &mut rng).unwrap()] += 1;
+        }
+        for (count, expected) in counts.into_iter().zip([0.6, 0.3, 0.1]) {
+            let share = count as f32 / draws as f32;
+            assert!((share - expected).abs() < 0.02, "{counts:?}");
+        }
+    }
+
+    #[test]
+    fn locked_slots_survive_restocks_until_bought() {
+        let config = test_utils::game_config();
+        let actor_types = test_utils::actor_types();
+        let mut shop = Shop::new(config.game.shop_rules());
+        let mut rng = GameRng::new(0);
+        let mut gold = 1000;
+        shop.restock(&actor_types, &mut gold, 1, &mut rng);
+        let kept = shop.stock().nth(1).unwrap().clone();
+        shop.toggle_lock(1);
+
+        for _ in 0..20 {
+            shop.restock(&actor_types, &mut gold, 1, &mut rng);
+            assert_eq!(shop.stock().nth(1), Some(&kept));
+            assert!(shop.is_locked(1));
+            assert_eq!(shop.stock().filter(|id| **id == kept).count(), 1);
+        }
+
+        let actor_type = actor_types.get(&kept).unwrap();
+        let slot = shop.buy_item(1, &kept, actor_type, &mut gold).unwrap();
+        assert_eq!(
+            slot,
+            ShopSlot {
+                index: 1,
+                locked: true
+            }
+        );
+        assert!((0..shop.stock().count()).all(|index| !shop.is_locked(index)));
+
+        // Cancelling the purchase puts the item back, still locked.
+        shop.return_item(&kept, actor_type, slot, &mut gold);
+        assert_eq!(shop.stock().nth(1), Some(&kept));
+        assert!(shop.is_locked(1));
+    }
+
+    #[test]
+    fn restocks_never_offer_a_locked_type_twice() {
+        let config = test_utils::game_config();
+        let actor_types = test_utils::actor_types();
+        for seed in 0..200 {
+            let mut shop = Shop::new(config.game.shop_rules());
+            let mut rng = GameRng::new(seed);
+            let mut gold = 1000;
+            shop.restock(&actor_types, &mut gold, 1, &mut rng);
+            shop.toggle_lock(0);
+            shop.toggle_lock(2);
+            for _ in 0..5 {
+                shop.restock(&actor_types, &mut gold, 1, &mut rng);
+                let stock: Vec<_> = shop.stock().collect();
+                for (index, actor_type_id) in stock.iter().enumerate() {
+                    assert!(
+                        !stock[index + 1..].contains(actor_type_id),
+                        "seed {seed}: {stock:?}"
+                    );
+                }
             }
         }
-        false
+    }
+
+    #[test]
+    fn free_restocks_and_slots_follow_the_rules() {
+        let rules = ShopRules {
+            restock_cost: 2.0,
+            restock_multiplier: 2.0,
+            slots: 2,
+            max_slots: 4,
+            rounds_per_extra_slot: 2,
+            free_restocks_per_round: 1,
+            reset_restock_cost: true,
+            rarity_weights: Vec::new(),
+        };
+        assert_eq!(
+            [1, 2, 3, 5, 7, 9].map(|round| rules.slots(round)),
+            [2, 2, 3, 4, 4, 4]
+        );
+
+        let actor_types = test_utils::actor_types();
+        let mut shop = Shop::new(rules);
+        let mut rng = GameRng::new(0);
+        let mut gold = 100;
+        shop.restock(&actor_types, &mut gold, 1, &mut rng);
+        assert_eq!((gold, shop.stock().count()), (100, 2));
+        shop.restock(&actor_types, &mut gold, 1, &mut rng);
+        shop.restock(&actor_types, &mut gold, 1, &mut rng);
+        assert_eq!((gold, shop.restock_cost()), (94, 8));
+
+        shop.new_round();
+        assert_eq!(shop.restock_cost(), 0);
+        shop.restock(&actor_types, &mut gold, 3, &mut rng);
+        assert_eq!(
+            (gold, shop.restock_cost(), shop.stock().count()),
+            (94, 2, 3)
+        );
+    }
+
+    #[test]
+    fn restock_skips_types_before_their_round() {
+        let actor_types = test_utils::actor_types();
+        let late: Vec<&ActorTypeId> = actor_types
+            .iter()
+            .filter(|(_, actor_type)| actor_type.min_round.is_some_and(|round| round > 1))
+            .map(|(actor_type_id, _)| actor_type_id)
+            .collect();
+        assert!(!late.is_empty());
+
+        let config = test_utils::game_config();
+        let mut shop = Shop::new(config.game.shop_rules());
+        let mut rng = GameRng::new(0);
+        for _ in 0..200 {
+            let mut gold = 1000;
+            shop.restock(&actor_types, &mut gold, 1, &mut rng);
+            assert!(
+                shop.stock()
+                    .all(|actor_type_id| !late.contains(&actor_type_id))
+            );
+        }
     }
 }
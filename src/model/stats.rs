@@ -0,0 +1,159 @@
+//! Statistics kept across runs, in a versioned RON file.
+
+use std::collections::BTreeMap;
+
+use thiserror::Error;
+
+use crate::model::{actor_type::ActorTypeId, game::Game};
+
+/// Bump this whenever the saved shape of [`StatsFile`] changes.
+pub const STATS_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct StatsFile {
+    pub version: u32,
+    pub run_count: u32,
+    pub best_total_gold: u64,
+    pub highest_round: u32,
+    /// Most activations in a single turn.
+    pub longest_chain: u32,
+    /// How many of each actor type were bought, over every run.
+    pub bought_by_type: BTreeMap<ActorTypeId, u32>,
+}
+
+#[derive(serde::Deserialize)]
+struct StatsHeader {
+    version: u32,
+}
+
+#[derive(Error, Debug)]
+pub enum StatsError {
+    #[error("could not parse stats: {0}")]
+    Parse(#[from] ron::error::SpannedError),
+    #[error("could not write stats: {0}")]
+    Write(#[from] ron::Error),
+    #[error("unsupported stats version {0} (current is {STATS_VERSION})")]
+    UnsupportedVersion(u32),
+}
+
+impl Default for StatsFile {
+    fn default() -> Self {
+        Self {
+            version: STATS_VERSION,
+            run_count: 0,
+            best_total_gold: 0,
+            highest_round: 0,
+            longest_chain: 0,
+            bought_by_type: BTreeMap::new(),
+        }
+    }
+}
+
+impl StatsFile {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a finished run. A run recorded before, at its victory, is not
+    /// counted again; only what it did since is added.
+    pub fn record_run(&mut self, game: &Game) {
+        let stats = game.stats();
+        let recorded_bought = stats.recorded_bought.as_ref();
+        if recorded_bought.is_none() {
+            self.run_count += 1;
+        }
+        self.best_total_gold = self.best_total_gold.max(game.total_gold());
+        self.highest_round = self.highest_round.max(game.round());
+        self.longest_chain = self.longest_chain.max(stats.longest_chain);
+        for (actor_type_id, count) in &stats.bought_by_type {
+            let already = recorded_bought
+                .and_then(|bought| bought.get(actor_type_id))
+                .copied()
+                .unwrap_or(0);
+            *self
+                .bought_by_type
+                .entry(actor_type_id.clone())
+                .or_default() += count - already;
+        }
+    }
+
+    /// The most bought actor types first, ties in name order.
+    pub fn most_used(&self, count: usize) -> Vec<(&ActorTypeId, u32)> {
+        let mut result: Vec<_> = self
+            .bought_by_type
+            .iter()
+            .map(|(actor_type_id, bought)| (actor_type_id, *bought))
+            .collect();
+        result.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        result.truncate(count);
+        result
+    }
+
+    pub fn to_ron(&self) -> Result<String, StatsError> {
+        Ok(ron::ser::to_string_pretty(
+            self,
+            ron::ser::PrettyConfig::default(),
+        )?)
+    }
+
+    pub fn from_ron(text: &str) -> Result<Self, StatsError> {
+        let header: StatsHeader = ron::from_str(text)?;
+        match header.version {
+            STATS_VERSION => Ok(ron::from_str(text)?),
+            version => Err(StatsError::UnsupportedVersion(version)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bevy::math::IVec2;
+
+    use super::*;
+    use crate::model::test_utils;
+
+    #[test]
+    fn runs_add_up() {
+        let config = test_utils::game_config();
+        let mut game = Game::with_seed(&config.game, test_utils::actor_types(), 0);
+        game.set_gold(100);
+        game.restock();
+        let bought = game.shop().stock().next().unwrap().clone();
+        assert!(game.buy_item(&bought, 0).is_some());
+        game.new_actor(&bought, IVec2::new(1, 0)).unwrap();
+        game.record_chain(7);
+
+        let mut stats = StatsFile::new();
+        stats.record_run(&game);
+        stats.record_run(&game);
+        let stats = StatsFile::from_ron(&stats.to_ron().unwrap()).unwrap();
+        assert_eq!(stats.run_count, 2);
+        assert_eq!(stats.longest_chain, 7);
+        assert_eq!(stats.highest_round, 1);
+        assert_eq!(stats.most_used(3), vec![(&bought, 2)]);
+    }
+
+    #[test]
+    fn endless_runs_are_counted_once() {
+        let config = test_utils::game_config();
+        let mut game = Game::with_seed(&config.game, test_utils::actor_types(), 0);
+        game.set_gold(100);
+        game.restock();
+        let bought = game.shop().stock().next().unwrap().clone();
+        assert!(game.buy_item(&bought, 0).is_some());
+
+        // recorded at the victory, then again once the endless run is over
+        let mut stats = StatsFile::new();
+        stats.record_run(&game);
+        game.mark_recorded();
+        let bought_later = game.shop().stock().next().unwrap().clone();
+        assert!(game.buy_item(&bought_later, 0).is_some());
+        game.record_chain(9);
+        stats.record_run(&game);
+        assert_eq!(stats.run_count, 1);
+        assert_eq!(stats.longest_chain, 9);
+        let bought_count: u32 = stats.bought_by_type.values().sum();
+        assert_eq!(bought_count, 2);
+        assert_eq!(stats.bought_by_type[&bought_later], 1);
+    }
+}
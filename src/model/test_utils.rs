@@ -0,0 +1,22 @@
+//! Loads the shipped data files, for tests that need real actor types.
+
+use crate::{
+    data::game_config::GameConfig,
+    model::{actor_types::ActorTypes, rounds::RoundSchedule},
+};
+
+fn load<T: serde::de::DeserializeOwned>(path: &str) -> T {
+    toml::from_str(&std::fs::read_to_string(path).unwrap()).unwrap()
+}
+
+pub(crate) fn game_config() -> GameConfig {
+    load("assets/game.config.toml")
+}
+
+pub(crate) fn actor_types() -> ActorTypes {
+    load("assets/all.actor_types.toml")
+}
+
+pub(crate) fn round_schedule() -> RoundSchedule {
+    load("assets/all.rounds.toml")
+}
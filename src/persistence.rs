@@ -0,0 +1,178 @@
+//! Keeps the in-progress run on disk, so it can be continued from the main menu,
+//! along with the last exported replay and the stats of past runs. They all go
+//! under [`data_dir`], so they do not depend on where the game is launched from.
+
+use std::path::PathBuf;
+
+use bevy::prelude::*;
+
+use crate::{
+    demo::GameplayState,
+    model::{game::Game, replay::ReplayFile, save::SaveFile, stats::StatsFile},
+};
+
+pub(super) fn plugin(app: &mut App) {
+    #[cfg(not(target_family = "wasm"))]
+    {
+        app.add_systems(OnEnter(GameplayState::Placement), write_save);
+        app.add_systems(
+            OnEnter(GameplayState::GameOver),
+            (delete_save, record_finished_run),
+        );
+        // Written again if the run goes on in endless mode.
+        app.add_systems(
+            OnEnter(GameplayState::Victory),
+            (delete_save, record_finished_run),
+        );
+    }
+}
+
+/// A save picked from the main menu, waiting for the gameplay screen to restore it.
+#[derive(Resource, Debug, Clone)]
+pub struct PendingSave(pub SaveFile);
+
+fn save_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("save.ron"))
+}
+
+pub fn has_save() -> bool {
+    save_path().is_some_and(|path| path.exists())
+}
+
+pub fn read_save() -> Option<SaveFile> {
+    let text = std::fs::read_to_string(save_path()?)
+        .inspect_err(|err| warn!("could not read save: {err}"))
+        .ok()?;
+    SaveFile::from_ron(&text)
+        .inspect_err(|err| warn!("{err}"))
+        .ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn write_save(game: Res<Game>) {
+    let Some(path) = save_path() else {
+        return;
+    };
+    match SaveFile::new(&game).to_ron() {
+        Ok(text) => {
+            if let Err(err) = write_data_file(&path, text) {
+                warn!("could not write save: {err}");
+            }
+        }
+        Err(err) => warn!("{err}"),
+    }
+}
+
+#[cfg(not(target_family = "wasm"))]
+fn delete_save() {
+    let Some(path) = save_path() else {
+        return;
+    };
+    if path.exists() {
+        if let Err(err) = std::fs::remove_file(path) {
+            warn!("could not delete save: {err}");
+        }
+    }
+}
+
+fn replay_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("replay.ron"))
+}
+
+pub fn has_replay() -> bool {
+    replay_path().is_some_and(|path| path.exists())
+}
+
+pub fn read_replay() -> Option<ReplayFile> {
+    let text = std::fs::read_to_string(replay_path()?)
+        .inspect_err(|err| warn!("could not read replay: {err}"))
+        .ok()?;
+    ReplayFile::from_ron(&text)
+        .inspect_err(|err| warn!("{err}"))
+        .ok()
+}
+
+/// Returns where the replay went, if it could be written.
+#[cfg(not(target_family = "wasm"))]
+pub fn write_replay(replay: &ReplayFile) -> Option<PathBuf> {
+    let path = replay_path()?;
+    let text = replay.to_ron().inspect_err(|err| warn!("{err}")).ok()?;
+    write_data_file(&path, text)
+        .inspect_err(|err| warn!("could not write replay: {err}"))
+        .ok()?;
+    Some(path)
+}
+
+/// Where files that outlive a run go, following each platform's conventions.
+/// `None` when there is no such place, on the web or without a home directory.
+pub fn data_dir() -> Option<PathBuf> {
+    let env_dir = |name: &str| std::env::var_os(name).map(PathBuf::from);
+    let base = if cfg!(target_family = "wasm") {
+        None
+    } else if cfg!(target_os = "windows") {
+        env_dir("APPDATA")
+    } else if cfg!(target_os = "macos") {
+        env_dir("HOME").map(|home| home.join("Library/Application Support"))
+    } else {
+        env_dir("XDG_DATA_HOME").or_else(|| env_dir("HOME").map(|home| home.join(".local/share")))
+    };
+    base.map(|base| base.join("bj6"))
+}
+
+/// Writes a file under `data_dir()`, creating the directory on first use.
+#[cfg(not(target_family = "wasm"))]
+fn write_data_file(path: &std::path::Path, text: String) -> std::io::Result<()> {
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, text)
+}
+
+fn stats_path() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("stats.ron"))
+}
+
+/// Empty stats when there are none yet, or they can't be read.
+pub fn read_stats() -> StatsFile {
+    load_stats().unwrap_or_default()
+}
+
+/// Empty stats when there is no file yet. `None` when there is one but it
+/// can't be read, so that it is not written over.
+fn load_stats() -> Option<StatsFile> {
+    let path = stats_path()?;
+    if !path.exists() {
+        return Some(StatsFile::new());
+    }
+    let text = std::fs::read_to_string(&path)
+        .inspect_err(|err| warn!("could not read stats: {err}"))
+        .ok()?;
+    StatsFile::from_ron(&text)
+        .inspect_err(|err| warn!("{err}"))
+        .ok()
+}
+
+#[cfg(not(target_family = "wasm"))]
+pub fn write_stats(stats: &StatsFile) {
+    let Some(path) = stats_path() else {
+        return;
+    };
+    let Ok(text) = stats.to_ron().inspect_err(|err| warn!("{err}")) else {
+        return;
+    };
+    if let Err(err) = write_data_file(&path, text) {
+        warn!("could not write stats: {err}");
+    }
+}
+
+/// Adds the run that just ended to the stats on disk.
+#[cfg(not(target_family = "wasm"))]
+fn record_finished_run(mut game: ResMut<Game>) {
+    let Some(mut stats) = load_stats() else {
+        warn!("the stats file was left as is, this run is not in it");
+        return;
+    };
+    stats.record_run(&game);
+    write_stats(&stats);
+    game.mark_recorded();
+}
@@ -6,7 +6,7 @@ use bevy::prelude::*;
 use crate::{
     asset_tracking::ResourceHandles,
     data::game_config::{GameConfig, GameConfigHandle},
-    model::actor_types::ActorTypesHandle,
+    model::{actor_types::ActorTypesHandle, rounds::RoundScheduleHandle},
     screens::Screen,
     theme::prelude::*,
 };
@@ -31,6 +31,7 @@ fn spawn_loading_screen(mut commands: Commands, asset_server: Res<AssetServer>)
     let actor_types_handle = ActorTypesHandle(asset_server.load("all.actor_types.toml"));
     commands.insert_resource(game_config_handle);
     commands.insert_resource(actor_types_handle);
+    commands.insert_resource(RoundScheduleHandle(asset_server.load("all.rounds.toml")));
 }
 
 fn enter_gameplay_screen(mut next_screen: ResMut<NextState<Screen>>) {
@@ -7,7 +7,7 @@ mod title;
 
 use bevy::prelude::*;
 
-use crate::demo::GameplayState;
+use crate::demo::{GameplayState, replay::Replay};
 
 pub(super) fn plugin(app: &mut App) {
     app.init_state::<Screen>();
@@ -33,6 +33,14 @@ pub enum Screen {
     Gameplay,
 }
 
-fn set_placement_substate(mut next_state: ResMut<NextState<GameplayState>>) {
-    next_state.set(GameplayState::Placement);
+fn set_placement_substate(
+    mut next_state: ResMut<NextState<GameplayState>>,
+    replay: Option<Res<Replay>>,
+) {
+    // A replay viewed from the main menu goes straight to playing it.
+    if replay.is_some_and(|replay| replay.viewer) {
+        next_state.set(GameplayState::Turn);
+    } else {
+        next_state.set(GameplayState::Placement);
+    }
 }
@@ -0,0 +1,255 @@
+//! Headless turn simulation, used by the `bj6-sim` binary.
+//!
+//! A [`Scenario`] describes a board in TOML. It is turned into a [`Game`] and
+//! run through the [`Runner`] without any Bevy app, so balance checks and bug
+//! reproductions can be scripted from text files.
+
+use std::path::Path;
+
+use bevy::math::IVec2;
+use thiserror::Error;
+
+use crate::{
+    data::game_config::GameConfig,
+    model::{
+        actor_type::ActorTypeId,
+        actor_types::{ActorTypeError, ActorTypes},
+        direction::Dir,
+        game::Game,
+        income::Income,
+        rounds::{RoundSchedule, RoundScheduleError},
+        runner::{Cmd, Runner},
+    },
+};
+
+#[derive(Error, Debug)]
+pub enum SimError {
+    #[error("could not read {path}: {source}")]
+    Io {
+        path: String,
+        source: std::io::Error,
+    },
+    #[error("could not parse {path}: {source}")]
+    Toml {
+        path: String,
+        source: toml::de::Error,
+    },
+    #[error("invalid actor types:\n{}", join_lines(.0))]
+    InvalidActorTypes(Vec<ActorTypeError>),
+    #[error("invalid round schedule:\n{}", join_lines(.0))]
+    InvalidRoundSchedule(Vec<RoundScheduleError>),
+    #[error("unknown actor type `{0}`")]
+    UnknownActorType(String),
+    #[error("coord {0} is already taken")]
+    CoordAlreadyTaken(IVec2),
+}
+
+/// A board description, as read from a `.board.toml` file.
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct Scenario {
+    /// Overrides `game.start_gold` from the game config.
+    pub gold: Option<u64>,
+    /// Seed of the game's rng, random when missing.
+    pub seed: Option<u64>,
+    /// Number of turns to run, unless the run ends sooner.
+    #[serde(default = "default_turns")]
+    pub turns: u32,
+    #[serde(default)]
+    pub actors: Vec<ScenarioActor>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+pub struct ScenarioActor {
+    pub actor_type: String,
+    pub coord: IVec2,
+    /// Defaults to the actor type's own `looks_to`.
+    pub looks_to: Option<Dir>,
+}
+
+fn join_lines(errors: &[impl ToString]) -> String {
+    errors
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn default_turns() -> u32 {
+    1
+}
+
+/// The outcome of a single simulated turn.
+#[derive(Debug, Clone)]
+pub struct TurnReport {
+    pub turn: u32,
+    pub round: u32,
+    pub cmds: Vec<Cmd>,
+    pub gold_this_turn: u64,
+    /// End of turn income, on top of `gold_this_turn`.
+    pub income: Income,
+    pub gold: u64,
+    pub total_gold: u64,
+    pub turns_left: u64,
+    pub game_over: bool,
+    /// The final round was won, the run stops there.
+    pub victory: bool,
+}
+
+pub fn load_toml<T: serde::de::DeserializeOwned>(path: &Path) -> Result<T, SimError> {
+    let text = std::fs::read_to_string(path).map_err(|source| SimError::Io {
+        path: path.display().to_string(),
+        source,
+    })?;
+    toml::from_str(&text).map_err(|source| SimError::Toml {
+        path: path.display().to_string(),
+        source,
+    })
+}
+
+impl Scenario {
+    /// Builds a fresh game and places every scenario actor on its board.
+    pub fn build_game(
+        &self,
+        game_config: &GameConfig,
+        actor_types: &ActorTypes,
+        schedule: &RoundSchedule,
+    ) -> Result<Game, SimError> {
+        // Sprites don't matter without rendering.
+        let errors = actor_types.validate(|_sprite_name| true);
+        if !errors.is_empty() {
+            return Err(SimError::InvalidActorTypes(errors));
+        }
+        let errors = schedule.validate();
+        if !errors.is_empty() {
+            return Err(SimError::InvalidRoundSchedule(errors));
+        }
+        let mut game = match self.seed {
+            Some(seed) => Game::with_seed(&game_config.game, actor_types.clone(), seed),
+            None => Game::new(&game_config.game, actor_types.clone()),
+        };
+        game.start_round(schedule);
+        if let Some(gold) = self.gold {
+            game.set_gold(gold);
+        }
+        for scenario_actor in &self.actors {
+            let actor_type_id = ActorTypeId::new(scenario_actor.actor_type.clone());
+            if actor_types.get(&actor_type_id).is_none() {
+                return Err(SimError::UnknownActorType(
+                    scenario_actor.actor_type.clone(),
+                ));
+            }
+            let actor_id = game
+                .new_actor(&actor_type_id, scenario_actor.coord)
+                .ok_or(SimError::CoordAlreadyTaken(scenario_actor.coord))?;
+            if let Some(looks_to) = scenario_actor.looks_to {
+                game.update_actor(&actor_id, |actor| actor.looks_to = looks_to);
+            }
+        }
+        Ok(game)
+    }
+}
+
+/// Runs up to `turns` turns, moving to the next round the same way the game does.
+pub fn run_turns(
+    mut game: Game,
+    turns: u32,
+    config: &GameConfig,
+    schedule: &RoundSchedule,
+) -> Vec<TurnReport> {
+    let mut reports = Vec::new();
+    for turn in 1..=turns {
+        let round = game.round();
+        let (mut new_game, cmds) = Runner::configured(game, config).run();
+        let income = new_game.end_turn();
+        let game_over = new_game.is_round_end() && !new_game.can_go_next_round();
+        let victory = !game_over && new_game.is_victory();
+        reports.push(TurnReport {
+            turn,
+            round,
+            cmds,
+            gold_this_turn: new_game.gold_this_turn(),
+            income,
+            gold: new_game.gold(),
+            total_gold: new_game.total_gold(),
+            turns_left: new_game.turns_left(),
+            game_over,
+            victory,
+        });
+        if game_over || victory {
+            break;
+        }
+        if new_game.is_round_end() {
+            new_game.next_round(schedule);
+        }
+        game = new_game;
+    }
+    reports
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::test_utils;
+
+    const PUSHER_CHAIN: &str = r#"
+        gold = 10
+        seed = 7
+
+        [[actors]]
+        actor_type = "pusher"
+        coord = [1, 0]
+
+        [[actors]]
+        actor_type = "hammer_time"
+        coord = [2, 0]
+        looks_to = "Up"
+    "#;
+
+    fn build(text: &str) -> Result<Game, SimError> {
+        let scenario: Scenario = toml::from_str(text).unwrap();
+        scenario.build_game(
+            &test_utils::game_config(),
+            &test_utils::actor_types(),
+            &test_utils::round_schedule(),
+        )
+    }
+
+    #[test]
+    fn scenarios_build_their_board() {
+        let scenario: Scenario = toml::from_str(PUSHER_CHAIN).unwrap();
+        assert_eq!(scenario.turns, 1);
+
+        let game = build(PUSHER_CHAIN).unwrap();
+        assert_eq!(game.gold(), 10);
+        assert_eq!(game.seed(), 7);
+        let pusher = game.board().coord_to_actor(IVec2::new(1, 0)).unwrap();
+        assert_eq!(pusher.actor_type_id, ActorTypeId::new("pusher".to_string()));
+        assert_eq!(pusher.looks_to, Dir::Right);
+        let hammer = game.board().coord_to_actor(IVec2::new(2, 0)).unwrap();
+        assert_eq!(hammer.looks_to, Dir::Up);
+
+        let unknown = "[[actors]]\nactor_type = \"nope\"\ncoord = [1, 0]";
+        assert!(matches!(build(unknown), Err(SimError::UnknownActorType(_))));
+        let taken = "[[actors]]\nactor_type = \"pusher\"\ncoord = [0, 0]";
+        assert!(matches!(build(taken), Err(SimError::CoordAlreadyTaken(_))));
+    }
+
+    #[test]
+    fn turns_are_reported_in_order() {
+        let config = test_utils::game_config();
+        let schedule = test_utils::round_schedule();
+        let game = build(PUSHER_CHAIN).unwrap();
+        let turns_left = game.turns_left();
+
+        let reports = run_turns(game, 2, &config, &schedule);
+        assert_eq!(reports.len(), 2);
+        for (index, report) in reports.iter().enumerate() {
+            assert_eq!(report.turn, index as u32 + 1);
+            assert_eq!(report.round, 1);
+            assert_eq!(report.turns_left, turns_left - report.turn as u64);
+            assert!(!report.cmds.is_empty());
+            assert!(!report.game_over && !report.victory);
+        }
+        assert!(reports[1].total_gold >= reports[0].total_gold);
+    }
+}
@@ -18,3 +18,8 @@ pub const BUTTON_PRESSED_BACKGROUND: Color = Color::srgb(0.239, 0.286, 0.600);
 pub const BUTTON_DISABLED_BACKGROUND: Color = Color::srgb(0.27, 0.27, 0.27);
 ///rgb(253, 254, 169)
 pub const BUTTON_SELECTED_BORDER: Color = Color::srgb(0.98, 0.99, 0.66);
+
+/// #ff6b6b
+pub const ERROR_TEXT: Color = Color::srgb(1.0, 0.420, 0.420);
+/// rgba(20, 20, 20, 0.9)
+pub const ERROR_BACKGROUND: Color = Color::srgba(0.08, 0.08, 0.08, 0.9);